@@ -9,6 +9,8 @@ import * as process from './process'
 import * as tauri from './tauri'
 import * as window from './window'
 import * as notification from './notification'
+import * as store from './store'
+import * as logger from './logger'
 
 export {
   cli,
@@ -19,5 +21,7 @@ export {
   process,
   tauri,
   window,
-  notification
+  notification,
+  store,
+  logger
 }
@@ -1,4 +1,4 @@
-import { OpenDialogOptions, SaveDialogOptions } from './types/dialog'
+import { OpenDialogOptions, SaveDialogOptions, MessageDialogOptions } from './types/dialog'
 import { promisified } from './tauri'
 
 /**
@@ -41,7 +41,47 @@ async function save(options: SaveDialogOptions = {}): Promise<String> {
   })
 }
 
+/**
+ * @name message
+ * @description Shows a message dialog with an ok button
+ * @param message the dialog message
+ * @param [options]
+ * @param [options.title]
+ * @param [options.kind='info']
+ * @returns promise resolving when the dialog is closed
+ */
+async function message(message: string, options: MessageDialogOptions = {}): Promise<void> {
+  return await promisified({
+    cmd: 'messageDialog',
+    options: {
+      message,
+      ...options
+    }
+  })
+}
+
+/**
+ * @name confirm
+ * @description Shows a dialog with an ok and a cancel button
+ * @param message the dialog message
+ * @param [options]
+ * @param [options.title]
+ * @param [options.kind='info']
+ * @returns promise resolving to whether the user confirmed the dialog
+ */
+async function confirm(message: string, options: MessageDialogOptions = {}): Promise<boolean> {
+  return await promisified({
+    cmd: 'confirmDialog',
+    options: {
+      message,
+      ...options
+    }
+  })
+}
+
 export {
   open,
-  save
+  save,
+  message,
+  confirm
 }
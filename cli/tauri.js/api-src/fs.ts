@@ -1,5 +1,5 @@
-import { promisified } from './tauri'
-import { BaseDirectory, FsOptions, FsTextFileOption, FsBinaryFileOption, FileEntry } from './types/fs'
+import { promisified, invoke } from './tauri'
+import { BaseDirectory, FsOptions, FsReadDirOptions, HashAlgorithm, FsTextFileOption, FsBinaryFileOption, FileEntry, DiskSpace, AppDirectory } from './types/fs'
 
 /**
  * reads a file as text
@@ -19,11 +19,12 @@ async function readTextFile(filePath: string, options: FsOptions = {}): Promise<
 
 /**
  * reads a file as binary
+ * files larger than 10 MiB are rejected; stream them with the http `saveTo` option or read them in the Rust side instead
  *
  * @param filePath path to the file
  * @param {Object} [options] configuration object
  * @param {BaseDirectory} [options.dir] base directory
- * @return {Promise<int[]>}
+ * @return {Promise<string>} promise resolving to the base64 encoded contents
  */
 async function readBinaryFile(filePath: string, options: FsOptions = {}): Promise<string> {
   return await promisified({
@@ -74,8 +75,8 @@ function uint8ArrayToString(arr: Uint8Array): string {
 
   let result = ''
   const arrLen = arr.length
-  for (let i = 0; i < arrLen; i++) {
-    const chunk = arr.subarray(i * CHUNK_SIZE, (i + 1) * CHUNK_SIZE)
+  for (let i = 0; i < arrLen; i += CHUNK_SIZE) {
+    const chunk = arr.subarray(i, i + CHUNK_SIZE)
     result += String.fromCharCode.apply(null, Array.from(chunk))
   }
   return result
@@ -94,6 +95,7 @@ function arrayBufferToBase64(buffer: ArrayBuffer): string {
 
 /**
  * writes a binary file
+ * the contents are sent base64 encoded, so files larger than 10 MiB are rejected
  *
  * @param file
  * @param file.path path of the file
@@ -111,23 +113,25 @@ async function writeBinaryFile(file: FsBinaryFileOption, options: FsOptions = {}
   }
 
   return await promisified({
-    cmd: 'writeFile',
-    file: file.path,
+    cmd: 'writeBinaryFile',
+    path: file.path,
     contents: arrayBufferToBase64(file.contents),
     options
   })
 }
 
 /**
- * list directory files
+ * list directory files with their metadata
+ * the entries outside the fs scope, e.g. symbolic links to other directories, are skipped
  *
  * @param dir path to the directory to read
  * @param [options] configuration object
  * @param [options.recursive] whether to list dirs recursively or not
+ * @param [options.maxDepth] how many subdirectory levels are read recursively; defaults to 16
  * @param [options.dir] base directory
- * @return
+ * @return promise resolving to the directory entries
  */
-async function readDir(dir: string, options: FsOptions = {}): Promise<FileEntry[]> {
+async function readDir(dir: string, options: FsReadDirOptions = {}): Promise<FileEntry[]> {
   return await promisified({
     cmd: 'readDir',
     path: dir,
@@ -224,6 +228,113 @@ async function renameFile(oldPath: string, newPath: string, options: FsOptions =
   })
 }
 
+/**
+ * computes the hex digest of a file, e.g. for integrity checks or dedup
+ * the file is streamed on a worker thread, so large files don't block the UI
+ *
+ * @param filePath path to the file
+ * @param algo the hash algorithm; md5 isn't collision resistant, so it should only be used for dedup
+ * @param [options] configuration object
+ * @param [options.dir] base directory
+ * @return promise resolving to the hex digest
+ */
+async function hashFile(filePath: string, algo: HashAlgorithm, options: FsOptions = {}): Promise<string> {
+  return await promisified({
+    cmd: 'hashFile',
+    path: filePath,
+    algo,
+    options
+  })
+}
+
+/**
+ * watches files or directories for changes made outside the app
+ * the changes are debounced and emitted as `file-change` events with the watcher id, the path and the change kind
+ * the paths must exist and be allowed by the fs scope; at most 32 watchers can be active at the same time
+ *
+ * @param paths the files or directories to watch
+ * @param [recursive] whether the directories are watched recursively
+ * @return promise resolving to the watcher id
+ */
+async function watch(paths: string[], recursive = false): Promise<number> {
+  return await promisified({
+    cmd: 'watch',
+    paths,
+    recursive
+  })
+}
+
+/**
+ * stops a watcher started with `watch`
+ *
+ * @param id the watcher id
+ * @return promise resolving when the watcher is stopped
+ */
+async function unwatch(id: number): Promise<void> {
+  return await promisified({
+    cmd: 'unwatch',
+    id
+  })
+}
+
+/**
+ * computes the total size of the files in an app directory, e.g. for a "storage used" settings screen
+ * the computation runs on a worker thread and can be cancelled with `cancelDirSize`
+ *
+ * @param id the computation id, used to cancel it; must be unique among the running computations
+ * @param base the app directory
+ * @param [path] a path inside the app directory
+ * @return promise resolving to the size in bytes, 0 if the directory doesn't exist
+ */
+async function dirSize(id: number, base: AppDirectory, path?: string): Promise<number> {
+  return await promisified({
+    cmd: 'dirSize',
+    id,
+    base,
+    path
+  })
+}
+
+/**
+ * cancels a computation started with `dirSize`, rejecting its promise
+ *
+ * @param id the computation id
+ */
+function cancelDirSize(id: number): void {
+  invoke({
+    cmd: 'cancelDirSize',
+    id
+  })
+}
+
+/**
+ * gets the free and total space of the volume containing the path
+ * the path must be allowed by the tauri.fs.diskSpaceScope config
+ *
+ * @param path a path on the volume
+ * @return promise resolving to the free and total bytes
+ */
+async function diskSpace(path: string): Promise<DiskSpace> {
+  return await promisified({
+    cmd: 'diskSpace',
+    path
+  })
+}
+
+/**
+ * shows a directory picker and grants the file system APIs access to the selected directory
+ * the promise is rejected if the tauri.fs.dynamicScope config is disabled
+ *
+ * @param [persist] whether the access is kept across sessions
+ * @return promise resolving to the selected directory path
+ */
+async function pickScopeFolder(persist: boolean = false): Promise<string> {
+  return await promisified({
+    cmd: 'pickScopeFolder',
+    persist
+  })
+}
+
 export {
   BaseDirectory as Dir,
   readTextFile,
@@ -235,5 +346,12 @@ export {
   removeDir,
   copyFile,
   removeFile,
-  renameFile
+  renameFile,
+  hashFile,
+  watch,
+  unwatch,
+  dirSize,
+  cancelDirSize,
+  diskSpace,
+  pickScopeFolder
 }
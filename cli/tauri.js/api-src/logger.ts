@@ -0,0 +1,83 @@
+import { invoke, promisified } from './tauri'
+
+export type LogLevel = 'trace' | 'debug' | 'info' | 'warn' | 'error'
+
+/**
+ * writes a line to the app log file
+ *
+ * @param level the log level
+ * @param message the log message
+ */
+function log(level: LogLevel, message: string): void {
+  invoke({
+    cmd: 'log',
+    level,
+    message
+  })
+}
+
+/**
+ * writes a trace line to the app log file
+ *
+ * @param message the log message
+ */
+function trace(message: string): void {
+  log('trace', message)
+}
+
+/**
+ * writes a debug line to the app log file
+ *
+ * @param message the log message
+ */
+function debug(message: string): void {
+  log('debug', message)
+}
+
+/**
+ * writes an info line to the app log file
+ *
+ * @param message the log message
+ */
+function info(message: string): void {
+  log('info', message)
+}
+
+/**
+ * writes a warn line to the app log file
+ *
+ * @param message the log message
+ */
+function warn(message: string): void {
+  log('warn', message)
+}
+
+/**
+ * writes an error line to the app log file
+ *
+ * @param message the log message
+ */
+function error(message: string): void {
+  log('error', message)
+}
+
+/**
+ * gets the path to the app log file
+ *
+ * @return promise resolving to the log file path
+ */
+async function getLogPath(): Promise<string> {
+  return await promisified({
+    cmd: 'getLogPath'
+  })
+}
+
+export {
+  log,
+  trace,
+  debug,
+  info,
+  warn,
+  error,
+  getLogPath
+}
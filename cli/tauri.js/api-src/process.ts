@@ -1,4 +1,12 @@
-import { promisified } from './tauri'
+import { invoke, promisified } from './tauri'
+import {
+  ChildProcess,
+  InstallLocation,
+  NetworkStatus,
+  Platform,
+  PowerState,
+  StartupMetrics
+} from './types/process'
 
 /**
  * spawns a process
@@ -19,6 +27,237 @@ async function execute(command: string, args?: string | string[]): Promise<strin
   })
 }
 
+/**
+ * opens a file or directory with the default application of the OS
+ * the path must be allowed by the `tauri.shell.openScope` config
+ *
+ * @param path the path to open
+ */
+function open(path: string): void {
+  invoke({
+    cmd: 'shellOpen',
+    path
+  })
+}
+
+/**
+ * registers the app as the "open with" handler of the given file extension for the current user
+ * the promise is rejected with a permission error if the platform requires elevation
+ * on macOS the file types must be declared by the app bundle, so the promise is always rejected
+ *
+ * @param ext the file extension, e.g. 'myext'
+ * @param [description] the file type description shown by the OS
+ * @return promise resolving once the association is registered
+ */
+async function registerFileAssociation(ext: string, description?: string): Promise<void> {
+  return await promisified({
+    cmd: 'registerFileAssociation',
+    ext,
+    description
+  })
+}
+
+/**
+ * checks whether the app is running from an installed location
+ * apps running from a disk image, a translocated or a temporary path can't update themselves,
+ * so the user should be asked to move the app, e.g. to the Applications folder
+ *
+ * @return promise resolving to the install location
+ */
+async function isInstalled(): Promise<InstallLocation> {
+  return await promisified({
+    cmd: 'isInstalled'
+  })
+}
+
+/**
+ * gets the platform the app is running on
+ * the os and arch are the Rust `std::env::consts` values, e.g. 'macos' and 'x86_64'
+ *
+ * @return promise resolving to the platform information; the version is null if it can't be determined
+ */
+async function getPlatform(): Promise<Platform> {
+  return await promisified({
+    cmd: 'getPlatform'
+  })
+}
+
+/**
+ * gets the active config, with the defaults of the unset options, e.g. to attach it to the bug reports
+ * the sensitive values, i.e. the updater env values and the proxy password, are redacted
+ *
+ * @return promise resolving to the config object
+ */
+async function getConfig(): Promise<{ [key: string]: unknown }> {
+  return await promisified({
+    cmd: 'getConfig'
+  })
+}
+
+/**
+ * gets the OS locale as a BCP 47 language tag, e.g. `en-US`, read from the platform settings rather than `navigator.language`
+ *
+ * @return promise resolving to the locale
+ */
+async function getLocale(): Promise<string> {
+  return await promisified({
+    cmd: 'getLocale'
+  })
+}
+
+/**
+ * gets the languages preferred by the user, in order of preference, as BCP 47 language tags
+ * the platforms don't notify the app when they change; the webview `languagechange` event can be used to query them again
+ *
+ * @return promise resolving to the language tags; the locale alone if the platform has no preferred languages list
+ */
+async function getPreferredLanguages(): Promise<string[]> {
+  return await promisified({
+    cmd: 'getPreferredLanguages'
+  })
+}
+
+/**
+ * gets the path to the app executable, e.g. for diagnostics or the self-update logic
+ * the path can reveal the user name, so it should only be sent with the user's consent
+ *
+ * @return promise resolving to the executable path
+ */
+async function getExePath(): Promise<string> {
+  return await promisified({
+    cmd: 'getExePath'
+  })
+}
+
+/**
+ * gets the working directory of the app process, e.g. to resolve the relative paths given on the command line
+ *
+ * @return promise resolving to the working directory path
+ */
+async function getCwd(): Promise<string> {
+  return await promisified({
+    cmd: 'getCwd'
+  })
+}
+
+/**
+ * gets the battery and power source state, e.g. to throttle the background work on battery
+ * the `power-changed` event is emitted with the new state when the AC adapter is connected or disconnected
+ *
+ * @return promise resolving to the power state; the battery percent is null on the devices without a battery
+ */
+async function getPowerState(): Promise<PowerState> {
+  return await promisified({
+    cmd: 'getPowerState'
+  })
+}
+
+/**
+ * installs the update reported by the `update-available` event, e.g. once the user confirms it
+ * the `updater-status` event is emitted with the `progress` messages of the updater, then with the `installed` status,
+ * so the user can be asked to relaunch the app
+ * it fails if an update is already being installed or if the app isn't running from an installed location
+ *
+ * @return promise resolving when the update is installed
+ */
+async function installUpdate(): Promise<void> {
+  return await promisified({
+    cmd: 'installUpdate'
+  })
+}
+
+/**
+ * gets the startup timing metrics, in milliseconds since the app started running, e.g. to track the startup regressions
+ * the phases that didn't run or didn't end yet are null, e.g. the server bind without the embedded server
+ * the `startup-metrics` event is emitted with the complete metrics when the main content is first initialized
+ *
+ * @return promise resolving to the startup metrics
+ */
+async function getStartupMetrics(): Promise<StartupMetrics> {
+  return await promisified({
+    cmd: 'getStartupMetrics'
+  })
+}
+
+/**
+ * gets the network connectivity state, e.g. to pause the sync while offline
+ * it's more reliable than `navigator.onLine`, but it doesn't detect the captive portals
+ * the `network-changed` event is emitted with the new status when the connectivity changes
+ *
+ * @return promise resolving to the network status
+ */
+async function getNetworkStatus(): Promise<NetworkStatus> {
+  return await promisified({
+    cmd: 'getNetworkStatus'
+  })
+}
+
+/**
+ * gets how long the user has been idle, i.e. the time since the last keyboard or mouse input
+ * on Linux it needs the GNOME idle monitor or the `xprintidle` tool
+ *
+ * @return promise resolving to the idle time in seconds
+ */
+async function getIdleTime(): Promise<number> {
+  return await promisified({
+    cmd: 'getIdleTime'
+  })
+}
+
+/**
+ * prevents the system from sleeping, e.g. during a media playback or a long task, or allows it again
+ * the display can still turn off, and the system can sleep again when the app exits
+ *
+ * @param enabled whether the system sleep is prevented
+ * @return promise resolving when the sleep inhibitor is acquired or released
+ */
+async function setPreventSleep(enabled: boolean): Promise<void> {
+  return await promisified({
+    cmd: 'setPreventSleep',
+    enabled
+  })
+}
+
+/**
+ * lists the running child processes spawned by the app, e.g. the updater and the sidecars
+ *
+ * @return promise resolving to the child processes
+ */
+async function listChildren(): Promise<ChildProcess[]> {
+  return await promisified({
+    cmd: 'listChildren'
+  })
+}
+
+/**
+ * terminates the running child processes spawned by the app, e.g. before exiting to avoid orphans
+ * the updater isn't killable, so a self-update isn't interrupted
+ *
+ * @return promise resolving to the terminated process ids
+ */
+async function killChildren(): Promise<number[]> {
+  return await promisified({
+    cmd: 'killChildren'
+  })
+}
+
 export {
-  execute
+  execute,
+  open,
+  registerFileAssociation,
+  isInstalled,
+  getPlatform,
+  getPowerState,
+  getConfig,
+  getLocale,
+  getPreferredLanguages,
+  getExePath,
+  getCwd,
+  installUpdate,
+  getStartupMetrics,
+  getNetworkStatus,
+  getIdleTime,
+  setPreventSleep,
+  listChildren,
+  killChildren
 }
@@ -0,0 +1,59 @@
+import { promisified } from './tauri'
+
+/**
+ * gets a value from the app store
+ *
+ * @param key the value key
+ * @return promise resolving to the value or null if the key doesn't exist
+ */
+async function get<T>(key: string): Promise<T | null> {
+  return await promisified({
+    cmd: 'storeGet',
+    key
+  })
+}
+
+/**
+ * sets a value on the app store
+ * the change is only written to disk when `save` is called
+ *
+ * @param key the value key
+ * @param value the value to store
+ */
+async function set(key: string, value: any): Promise<void> {
+  return await promisified({
+    cmd: 'storeSet',
+    key,
+    value
+  })
+}
+
+/**
+ * deletes a key from the app store
+ * the change is only written to disk when `save` is called
+ *
+ * @param key the value key
+ * @return promise resolving to whether the key existed or not
+ */
+async function remove(key: string): Promise<boolean> {
+  return await promisified({
+    cmd: 'storeDelete',
+    key
+  })
+}
+
+/**
+ * writes the app store to disk
+ */
+async function save(): Promise<void> {
+  return await promisified({
+    cmd: 'storeSave'
+  })
+}
+
+export {
+  get,
+  set,
+  remove,
+  save
+}
@@ -36,11 +36,32 @@ function transformCallback(callback?: (response: any) => void, once = false): st
   return identifier
 }
 
+interface InvokeResponse {
+  status: 'ok' | 'error'
+  data: any
+  error: any
+}
+
+function isInvokeResponse(response: any): response is InvokeResponse {
+  return typeof response === 'object' && response !== null &&
+    (response.status === 'ok' || response.status === 'error') &&
+    'data' in response && 'error' in response
+}
+
 async function promisified<T>(args: any): Promise<T> {
   return await new Promise((resolve, reject) => {
+    // the responses are `{ status, data, error }` envelopes,
+    // except for the custom commands that call the callbacks with a raw value
+    const settle = (fallback: (value: any) => void) => (response: any) => {
+      if (isInvokeResponse(response)) {
+        response.status === 'ok' ? resolve(response.data) : reject(response.error)
+      } else {
+        fallback(response)
+      }
+    }
     invoke({
-      callback: transformCallback(resolve),
-      error: transformCallback(reject),
+      callback: transformCallback(settle(resolve)),
+      error: transformCallback(settle(reject)),
       ...args
     })
   })
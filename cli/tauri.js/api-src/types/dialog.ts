@@ -6,3 +6,8 @@ export interface OpenDialogOptions {
 }
 
 export type SaveDialogOptions = Pick<OpenDialogOptions, 'filter' | 'defaultPath'>
+
+export interface MessageDialogOptions {
+  title?: string
+  kind?: 'info' | 'warning' | 'error'
+}
@@ -4,3 +4,14 @@ export interface Event<T> {
 }
 
 export type EventCallback<T> = (event: Event<T>) => void
+
+export type ErrorCategory = 'config' | 'io' | 'server' | 'webview' | 'command' | 'updater'
+
+/**
+ * the payload of the `command-error` event
+ */
+export interface CommandError {
+  category: ErrorCategory
+  message: string
+  details: string | null
+}
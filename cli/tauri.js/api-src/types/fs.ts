@@ -23,6 +23,12 @@ export interface FsOptions {
   dir?: BaseDirectory
 }
 
+export interface FsReadDirOptions extends FsOptions {
+  recursive?: boolean
+  // how many subdirectory levels are read recursively; defaults to 16
+  maxDepth?: number
+}
+
 export interface FsTextFileOption {
   path: string
   contents: string
@@ -38,6 +44,34 @@ export interface FileEntry {
   // name of the directory/file
   // can be null if the path terminates with `..`
   name?: string
+  // whether this entry is a directory
+  isDir: boolean
+  // size of this entry in bytes; the size of the directories depends on the file system
+  size: number
+  // last modification time in milliseconds since the UNIX epoch; null if the platform doesn't support it
+  modified: number | null
   // children of this entry if it's a directory; null otherwise
   children?: FileEntry[]
 }
+
+export type HashAlgorithm = 'sha256' | 'md5' | 'blake3'
+
+export type FileChangeKind = 'create' | 'write' | 'metadata' | 'remove' | 'rename'
+
+export interface FileChange {
+  // id of the watcher that reported the change
+  id: number
+  path: string
+  kind: FileChangeKind
+  // new path of the renamed paths
+  to?: string
+}
+
+export type AppDirectory = 'appData' | 'appCache'
+
+export interface DiskSpace {
+  // bytes available to the current user
+  free: number
+  // total size of the volume in bytes
+  total: number
+}
@@ -28,6 +28,12 @@ export interface HttpOptions {
   allowCompression: boolean
   responseType?: ResponseType
   bodyType: BodyType
+  /**
+   * the path of the file to stream the response body to, instead of reading it in memory
+   * the request then resolves to the file path
+   * the path must be allowed by the tauri.fs.scope config, and the writeFile or writeBinaryFile API must be whitelisted
+   */
+  saveTo?: string
 }
 
 export type PartialOptions = Omit<HttpOptions, 'method' | 'url'>
@@ -0,0 +1,40 @@
+export type NotInstalledReason = 'translocated' | 'diskImage' | 'temporary'
+
+export interface InstallLocation {
+  installed: boolean
+  reason: NotInstalledReason | null
+  path: string
+}
+
+export interface Platform {
+  os: 'linux' | 'macos' | 'windows' | string
+  arch: string
+  version: string | null
+  family: 'unix' | 'windows' | string
+}
+
+export interface PowerState {
+  onBattery: boolean
+  batteryPercent: number | null
+  charging: boolean
+}
+
+export interface StartupMetrics {
+  configLoad: number | null
+  contentSetup: number | null
+  webviewBuild: number | null
+  serverBind: number | null
+  initialized: number | null
+}
+
+export interface NetworkStatus {
+  online: boolean
+  localAddress: string | null
+  interfaces: string[]
+}
+
+export interface ChildProcess {
+  pid: number
+  name: string
+  killable: boolean
+}
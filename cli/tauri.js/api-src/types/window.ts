@@ -0,0 +1,17 @@
+export type StorageKind = 'cache' | 'cookies' | 'localStorage' | 'sessionStorage'
+
+export type MediaPermissionState = 'granted' | 'denied' | 'prompt' | 'unknown'
+
+export interface MediaPermissions {
+  camera: MediaPermissionState
+  microphone: MediaPermissionState
+}
+
+export interface Cookie {
+  name: string
+  value: string
+  path?: string
+  maxAge?: number
+  secure?: boolean
+  sameSite?: 'strict' | 'lax' | 'none'
+}
@@ -1,7 +1,9 @@
-import { invoke } from './tauri'
+import { invoke, promisified } from './tauri'
+import { Cookie, MediaPermissions, StorageKind } from './types/window'
 
 /**
  * sets the window title
+ * the {{app_name}} and {{version}} placeholders are resolved from the package config
  *
  * @param title the new title
  */
@@ -12,6 +14,271 @@ function setTitle(title: string): void {
   })
 }
 
+/**
+ * enables or disables the webview spellchecker on the editable content
+ * the setting is reset to the `tauri.window.spellcheck` config when the page is reloaded
+ *
+ * @param enabled whether the spellchecker is enabled or not
+ */
+function setSpellcheck(enabled: boolean): void {
+  invoke({
+    cmd: 'setSpellcheck',
+    enabled
+  })
+}
+
+/**
+ * checks whether the window is focused
+ *
+ * @return promise resolving to the window focus state
+ */
+async function isFocused(): Promise<boolean> {
+  return await promisified({
+    cmd: 'isFocused'
+  })
+}
+
+/**
+ * replaces the headers added to the fetch and XMLHttpRequest requests to the content origin, e.g. with a refreshed token
+ * they're kept until the content is reloaded, which restores the build.requestHeaders config
+ *
+ * @param headers the header names and values
+ */
+function setRequestHeaders(headers: { [name: string]: string }): void {
+  invoke({
+    cmd: 'setRequestHeaders',
+    headers
+  })
+}
+
+/**
+ * sets the macOS activation policy, e.g. 'accessory' for a menu bar utility without a dock icon
+ * switch back to 'regular' when showing a window; the other platforms only log a warning
+ *
+ * @param policy the activation policy
+ */
+function setActivationPolicy(policy: 'regular' | 'accessory' | 'prohibited'): void {
+  invoke({
+    cmd: 'setActivationPolicy',
+    policy
+  })
+}
+
+/**
+ * turns the confirmation asked before unloading the content on or off, e.g. off once the unsaved changes are saved
+ * requires the `tauri.window.confirmOnClose` config; the confirmation is on when the page loads
+ *
+ * @param enabled whether the confirmation is asked
+ */
+function setCloseConfirmation(enabled: boolean): void {
+  invoke({
+    cmd: 'setCloseConfirmation',
+    enabled
+  })
+}
+
+/**
+ * gets the window scale factor, i.e. the physical pixels per CSS pixel
+ * the `scale-changed` event is emitted with the new `{ scaleFactor, width, height }`, where width and height are the physical inner size,
+ * when the window moves to a display with another DPI
+ *
+ * @return promise resolving to the scale factor
+ */
+async function scaleFactor(): Promise<number> {
+  return await promisified({
+    cmd: 'scaleFactor'
+  })
+}
+
+/**
+ * gets the window theme
+ * resolves to the `tauri.window.theme` config if it's set, or the OS theme otherwise
+ * listen to the `theme-changed` event to be notified when the OS theme changes
+ *
+ * @return promise resolving to the window theme
+ */
+async function getTheme(): Promise<'light' | 'dark'> {
+  return await promisified({
+    cmd: 'getTheme'
+  })
+}
+
+/**
+ * sets whether the window is shown on all the workspaces, e.g. for an overlay that follows the user across the desktops
+ * it's kept when the window is hidden and shown again; logs a warning on platforms where it isn't supported
+ *
+ * @param value whether the window should be visible on all the workspaces
+ */
+function setVisibleOnAllWorkspaces(value: boolean): void {
+  invoke({
+    cmd: 'setVisibleOnAllWorkspaces',
+    value
+  })
+}
+
+/**
+ * opens the native print dialog for the current page
+ * silent printing to the default printer is rejected on platforms where it isn't supported
+ *
+ * @param [silent] whether to print to the default printer without showing the dialog
+ * @return promise resolving when the dialog is closed
+ */
+async function print(silent = false): Promise<void> {
+  return await promisified({
+    cmd: 'print',
+    silent
+  })
+}
+
+/**
+ * sets the badge count shown on the app launcher entry, e.g. the unread messages count
+ * currently only supported on Linux docks implementing the Unity launcher protocol
+ *
+ * @param [count] the badge count; 0 or null clears the badge
+ */
+function setBadge(count?: number | null): void {
+  invoke({
+    cmd: 'setBadge',
+    count
+  })
+}
+
+/**
+ * shows or hides the loading overlay managed by tauri
+ * the overlay is hidden after the timeout, so it never locks the app
+ *
+ * @param visible whether the overlay is shown or not
+ * @param [message] the message shown on the overlay
+ * @param [timeout] the time after which the overlay is hidden, in milliseconds; defaults to 30 seconds
+ */
+function setLoading(visible: boolean, message?: string, timeout?: number): void {
+  invoke({
+    cmd: 'setLoading',
+    visible,
+    message,
+    timeout
+  })
+}
+
+/**
+ * clears the webview storage
+ * the storage is cleared with the web APIs available to the page:
+ * 'cache' clears the Cache Storage and HttpOnly cookies can't be removed
+ *
+ * @param kinds the storage kinds to clear
+ * @return promise resolving to the storage kinds that were actually cleared
+ */
+async function clearCache(kinds: StorageKind[]): Promise<StorageKind[]> {
+  return await promisified({
+    cmd: 'clearCache',
+    kinds
+  })
+}
+
+/**
+ * gets the cookies of the app origin
+ * HttpOnly cookies aren't visible to the page so they're not returned
+ *
+ * @param [url] the URL to get the cookies for, which must have the app origin
+ * @return promise resolving to the cookie names and values
+ */
+async function getCookies(url?: string): Promise<Array<{ name: string, value: string }>> {
+  return await promisified({
+    cmd: 'getCookies',
+    url
+  })
+}
+
+/**
+ * sets a cookie on the app origin
+ *
+ * @param cookie the cookie to set
+ * @param [url] the URL to set the cookie for, which must have the app origin
+ * @return promise resolving when the cookie is set
+ */
+async function setCookie(cookie: Cookie, url?: string): Promise<void> {
+  return await promisified({
+    cmd: 'setCookie',
+    cookie,
+    url
+  })
+}
+
+/**
+ * deletes a cookie of the app origin
+ *
+ * @param name the cookie name
+ * @param [path] the cookie path, defaults to '/'
+ * @param [url] the URL to delete the cookie for, which must have the app origin
+ * @return promise resolving when the cookie is deleted
+ */
+async function deleteCookie(name: string, path?: string, url?: string): Promise<void> {
+  return await promisified({
+    cmd: 'deleteCookie',
+    name,
+    path,
+    url
+  })
+}
+
+/**
+ * gets the camera and microphone permission states
+ * requires the `tauri.security.mediaAccess` config
+ *
+ * @return promise resolving to the permission states; 'unknown' if the webview can't query them
+ */
+async function getMediaPermissions(): Promise<MediaPermissions> {
+  return await promisified({
+    cmd: 'getMediaPermissions'
+  })
+}
+
+/**
+ * requests the camera and/or microphone access, showing the platform prompt if it wasn't answered yet
+ * requires the `tauri.security.mediaAccess` config
+ *
+ * @param options the devices to request
+ * @param [options.camera] whether the camera access is requested
+ * @param [options.microphone] whether the microphone access is requested
+ * @return promise resolving to the permission states after the request
+ */
+async function requestMediaPermissions(options: { camera?: boolean, microphone?: boolean }): Promise<MediaPermissions> {
+  return await promisified({
+    cmd: 'requestMediaPermissions',
+    camera: options.camera,
+    microphone: options.microphone
+  })
+}
+
+/**
+ * registers a scheme resolving the `src` and `href` attributes starting with `<scheme>://` to the app assets
+ * e.g. after `registerAssetProtocol('asset')`, `<img src="asset://logo.png">` loads the bundled `logo.png`
+ * the built-in schemes such as 'http', 'file' or 'data' can't be registered
+ *
+ * @param scheme the scheme name
+ * @return promise resolving when the scheme is registered
+ */
+async function registerAssetProtocol(scheme: string): Promise<void> {
+  return await promisified({
+    cmd: 'registerAssetProtocol',
+    scheme
+  })
+}
+
+/**
+ * loads the given asset paths in the embedded server memory, e.g. the assets of the next view, so their requests don't read the disk
+ * it does nothing if the app isn't served by the embedded server
+ *
+ * @param paths the asset paths, e.g. `/js/settings.js`
+ * @return promise resolving when all the assets are loaded, or rejecting with the paths that couldn't be loaded
+ */
+async function prefetchAssets(paths: string[]): Promise<void> {
+  return await promisified({
+    cmd: 'prefetchAssets',
+    paths
+  })
+}
+
 /**
  * opens an URL on the user default browser
  *
@@ -26,5 +293,24 @@ function open(url: string): void {
 
 export {
   setTitle,
+  setSpellcheck,
+  isFocused,
+  scaleFactor,
+  setCloseConfirmation,
+  setActivationPolicy,
+  setRequestHeaders,
+  getTheme,
+  setLoading,
+  setBadge,
+  setVisibleOnAllWorkspaces,
+  print,
+  clearCache,
+  getCookies,
+  setCookie,
+  deleteCookie,
+  getMediaPermissions,
+  requestMediaPermissions,
+  registerAssetProtocol,
+  prefetchAssets,
   open
 }
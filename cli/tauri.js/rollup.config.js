@@ -19,6 +19,8 @@ export default [{
     'window': './api-src/window.ts',
     'cli': './api-src/cli.ts',
     'notification': './api-src/notification.ts',
+    'store': './api-src/store.ts',
+    'logger': './api-src/logger.ts',
   },
   treeshake:      true,
   perf:           true,
@@ -318,10 +318,12 @@ class Runner {
           document.head.insertBefore(headMutationObserverScript, document.head.firstChild)
         }
 
-        const tauriScript = document.createElement('script')
-        // eslint-disable-next-line @typescript-eslint/no-unsafe-assignment, @typescript-eslint/no-var-requires, @typescript-eslint/no-unsafe-member-access
-        tauriScript.text = require('../templates/tauri.js').default
-        document.head.insertBefore(tauriScript, document.head.firstChild)
+        if (cfg.build.injectBridge !== false) {
+          const tauriScript = document.createElement('script')
+          // eslint-disable-next-line @typescript-eslint/no-unsafe-assignment, @typescript-eslint/no-var-requires, @typescript-eslint/no-unsafe-member-access
+          tauriScript.text = require('../templates/tauri.js').default
+          document.head.insertBefore(tauriScript, document.head.firstChild)
+        }
 
         if (cfg.build.withGlobalTauri) {
           const tauriUmdScript = document.createElement('script')
@@ -112,9 +112,24 @@ switch (navigator.platform) {
     var _this = this;
 
     return new Promise(function (resolve, reject) {
+      // the responses are `{ status, data, error }` envelopes,
+      // except for the custom commands that call the callbacks with a raw value
+      function settle(fallback) {
+        return function (response) {
+          if (
+            typeof response === 'object' && response !== null &&
+            (response.status === 'ok' || response.status === 'error') &&
+            'data' in response && 'error' in response
+          ) {
+            response.status === 'ok' ? resolve(response.data) : reject(response.error)
+          } else {
+            fallback(response)
+          }
+        }
+      }
       _this.invoke(_objectSpread({
-        callback: _this.transformCallback(resolve),
-        error: _this.transformCallback(reject)
+        callback: _this.transformCallback(settle(resolve)),
+        error: _this.transformCallback(settle(reject))
       }, args))
     })
   }
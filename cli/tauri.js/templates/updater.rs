@@ -39,6 +39,20 @@ fn update() -> Result<(), String> {
   }
 }
 
+// prints the latest release version if it's newer than the current version
+fn check() -> Result<(), String> {
+  let github_release = tauri::updater::github::get_latest_release("jaemk", "self_update")
+    .map_err(|_| "Could not fetch latest release")?;
+  let version = github_release.tag.trim_start_matches('v');
+  if tauri::version::compare(env!("CARGO_PKG_VERSION"), version)
+    .map_err(|_| "Could not compare versions")?
+    == 1
+  {
+    println!("{}", version);
+  }
+  Ok(())
+}
+
 fn restart_app(app_command: String) -> Result<(), String> {
   let mut system = tauri::process::System::new();
   let parent_process = tauri::process::get_parent_process(&mut system)
@@ -62,7 +76,12 @@ fn run_updater() -> Result<(), String> {
 }
 
 fn main() {
-  match run_updater() {
+  let result = if std::env::args().any(|arg| arg == "--check") {
+    check()
+  } else {
+    run_updater()
+  };
+  match result {
     Ok(_) => {}
     Err(err) => panic!(err),
   };
@@ -25,6 +25,10 @@ pub fn main() -> Result<(), Box<dyn Error>> {
         println!("cargo:rerun-if-changed={}", tauri_dir_string);
 
         let original_config_path = Path::new(&tauri_dir_string).join("tauri.conf.json");
+        println!(
+          "cargo:rustc-env=TAURI_CONFIG_PATH={}",
+          original_config_path.display()
+        );
         let original_config = read_to_string(original_config_path)?;
 
         write!(config_file, "{}", original_config)?;
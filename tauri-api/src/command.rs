@@ -1,4 +1,12 @@
-use std::process::{Child, Command, Stdio};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::{self, Read};
+use std::process::{
+  Child, ChildStderr, ChildStdin, ChildStdout, Command, ExitStatus, Output, Stdio,
+};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 #[cfg(windows)]
 use std::os::windows::process::CommandExt;
@@ -8,6 +16,103 @@ const CREATE_NO_WINDOW: u32 = 0x08000000;
 
 use tauri_utils::platform;
 
+/// A child process spawned with `spawn_relative_command`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrackedChild {
+  /// The process id.
+  pub pid: u32,
+  /// The command the process was spawned with.
+  pub name: String,
+  /// Whether `kill_children` terminates the process.
+  /// It's `false` for the processes spawned with `spawn_protected_command`, e.g. the updater.
+  pub killable: bool,
+}
+
+/// A child process handle shared by a `SpawnedChild` and the tracked children.
+struct Tracked {
+  child: Arc<Mutex<Child>>,
+  info: TrackedChild,
+}
+
+/// The child processes spawned with `spawn_relative_command`.
+static CHILDREN: Lazy<Mutex<Vec<Tracked>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// How often `SpawnedChild::wait` checks whether the process exited.
+const WAIT_INTERVAL: Duration = Duration::from_millis(10);
+
+/// A child process spawned with `spawn_relative_command`.
+///
+/// The process handle is shared with the tracked children, so they can tell with `try_wait`
+/// that it exited, and `kill_children` never signals a process id that was reused.
+pub struct SpawnedChild {
+  child: Arc<Mutex<Child>>,
+  pid: u32,
+  /// The handle of the piped stdin, if any.
+  pub stdin: Option<ChildStdin>,
+  /// The handle of the piped stdout, if any.
+  pub stdout: Option<ChildStdout>,
+  /// The handle of the piped stderr, if any.
+  pub stderr: Option<ChildStderr>,
+}
+
+impl SpawnedChild {
+  /// The process id.
+  pub fn id(&self) -> u32 {
+    self.pid
+  }
+
+  /// Gets the exit status of the process if it exited, without blocking.
+  pub fn try_wait(&self) -> io::Result<Option<ExitStatus>> {
+    self.child.lock().expect("Failed to lock child").try_wait()
+  }
+
+  /// Waits for the process to exit. The stdin is closed first, so the process doesn't wait for its input.
+  ///
+  /// The handle isn't locked while waiting, so `kill_children` can still terminate the process.
+  pub fn wait(&mut self) -> io::Result<ExitStatus> {
+    drop(self.stdin.take());
+    loop {
+      if let Some(status) = self.try_wait()? {
+        return Ok(status);
+      }
+      std::thread::sleep(WAIT_INTERVAL);
+    }
+  }
+
+  /// Waits for the process to exit, collecting its piped stdout and stderr.
+  pub fn wait_with_output(mut self) -> io::Result<Output> {
+    drop(self.stdin.take());
+    // the stderr is read on another thread, so the process doesn't block on a full pipe
+    let stderr = self.stderr.take().map(|mut stderr| {
+      std::thread::spawn(move || {
+        let mut buffer = Vec::new();
+        stderr.read_to_end(&mut buffer).map(|_| buffer)
+      })
+    });
+    let mut stdout = Vec::new();
+    if let Some(mut pipe) = self.stdout.take() {
+      pipe.read_to_end(&mut stdout)?;
+    }
+    let stderr = match stderr {
+      Some(reader) => reader
+        .join()
+        .map_err(|_| io::Error::new(io::ErrorKind::Other, "failed to read the stderr"))??,
+      None => Vec::new(),
+    };
+    Ok(Output {
+      status: self.wait()?,
+      stdout,
+      stderr,
+    })
+  }
+
+  /// Kills the process.
+  pub fn kill(&mut self) -> io::Result<()> {
+    self.child.lock().expect("Failed to lock child").kill()
+  }
+}
+
 /// Gets the output of the given command.
 #[cfg(not(windows))]
 pub fn get_output(cmd: String, args: Vec<String>, stdout: Stdio) -> crate::Result<String> {
@@ -54,34 +159,212 @@ pub fn command_path(command: String) -> crate::Result<String> {
   }
 }
 
+/// The stdin, stdout and stderr dispositions of a spawned process.
+///
+/// Every stream is inherited from the current process by default.
+/// Use `Stdio::piped()` to read or write a stream through the returned `Child`,
+/// or `Stdio::null()` to discard it.
+pub struct SpawnStdio {
+  /// The stdin disposition.
+  pub stdin: Stdio,
+  /// The stdout disposition.
+  pub stdout: Stdio,
+  /// The stderr disposition.
+  pub stderr: Stdio,
+}
+
+impl Default for SpawnStdio {
+  fn default() -> Self {
+    Self {
+      stdin: Stdio::inherit(),
+      stdout: Stdio::inherit(),
+      stderr: Stdio::inherit(),
+    }
+  }
+}
+
+/// Validates the names of the environment variables to set on a spawned process.
+fn validate_env(env: &HashMap<String, String>) -> crate::Result<()> {
+  for key in env.keys() {
+    if key.is_empty() || key.contains('=') {
+      return Err(
+        crate::Error::Command(format!("invalid environment variable name '{}'", key)).into(),
+      );
+    }
+  }
+  Ok(())
+}
+
 /// Spawns a process with a command string relative to the current executable path.
 /// For example, if your app bundles two executables, you don't need to worry about its path and just run `second-app`.
-#[cfg(windows)]
+///
+/// The process inherits the environment of the current process.
+/// The `env` variables are added to it, taking precedence over the inherited variables with the same name.
+///
+/// The `stdio` streams that are piped are available on the returned `SpawnedChild`.
 pub fn spawn_relative_command(
   command: String,
   args: Vec<String>,
-  stdout: Stdio,
-) -> crate::Result<Child> {
-  let cmd = command_path(command)?;
+  stdio: SpawnStdio,
+  env: &HashMap<String, String>,
+) -> crate::Result<SpawnedChild> {
+  spawn_relative(command, args, stdio, env, true)
+}
+
+/// Spawns a process like `spawn_relative_command`, protected so `kill_children` doesn't terminate it.
+/// The updater is protected so a self-update isn't killed mid-flight.
+///
+/// The process is tracked as protected when it's spawned, so it can't be killed in between.
+pub fn spawn_protected_command(
+  command: String,
+  args: Vec<String>,
+  stdio: SpawnStdio,
+  env: &HashMap<String, String>,
+) -> crate::Result<SpawnedChild> {
+  spawn_relative(command, args, stdio, env, false)
+}
+
+/// Spawns and tracks a process with a command string relative to the current executable path.
+#[cfg(windows)]
+fn spawn_relative(
+  command: String,
+  args: Vec<String>,
+  stdio: SpawnStdio,
+  env: &HashMap<String, String>,
+  killable: bool,
+) -> crate::Result<SpawnedChild> {
+  validate_env(env)?;
+  let cmd = command_path(command.clone())?;
   Ok(
     Command::new(cmd)
       .args(args)
+      .envs(env)
       .creation_flags(CREATE_NO_WINDOW)
-      .stdout(stdout)
-      .spawn()?,
+      .stdin(stdio.stdin)
+      .stdout(stdio.stdout)
+      .stderr(stdio.stderr)
+      .spawn()
+      .map(|child| track(child, command, killable))?,
   )
 }
 
-/// Spawns a process with a command string relative to the current executable path.
-/// For example, if your app bundles two executables, you don't need to worry about its path and just run `second-app`.
+/// Spawns and tracks a process with a command string relative to the current executable path.
 #[cfg(not(windows))]
-pub fn spawn_relative_command(
+fn spawn_relative(
   command: String,
   args: Vec<String>,
-  stdout: Stdio,
-) -> crate::Result<Child> {
-  let cmd = command_path(command)?;
-  Ok(Command::new(cmd).args(args).stdout(stdout).spawn()?)
+  stdio: SpawnStdio,
+  env: &HashMap<String, String>,
+  killable: bool,
+) -> crate::Result<SpawnedChild> {
+  validate_env(env)?;
+  let cmd = command_path(command.clone())?;
+  Ok(
+    Command::new(cmd)
+      .args(args)
+      .envs(env)
+      .stdin(stdio.stdin)
+      .stdout(stdio.stdout)
+      .stderr(stdio.stderr)
+      .spawn()
+      .map(|child| track(child, command, killable))?,
+  )
+}
+
+/// Adds the given child process to the tracked children.
+fn track(mut child: Child, name: String, killable: bool) -> SpawnedChild {
+  let pid = child.id();
+  let stdin = child.stdin.take();
+  let stdout = child.stdout.take();
+  let stderr = child.stderr.take();
+  let child = Arc::new(Mutex::new(child));
+  CHILDREN
+    .lock()
+    .expect("Failed to lock children")
+    .push(Tracked {
+      child: child.clone(),
+      info: TrackedChild {
+        pid,
+        name,
+        killable,
+      },
+    });
+  SpawnedChild {
+    child,
+    pid,
+    stdin,
+    stdout,
+    stderr,
+  }
+}
+
+/// Removes the tracked children that exited, i.e. whose exit status is available.
+/// The exited processes are reaped, so they don't linger as zombies.
+fn prune(children: &mut Vec<Tracked>) {
+  children.retain(|tracked| {
+    match tracked
+      .child
+      .lock()
+      .expect("Failed to lock child")
+      .try_wait()
+    {
+      Ok(None) => true,
+      _ => false,
+    }
+  });
+}
+
+/// Gets the child processes spawned with `spawn_relative_command` that are still running.
+pub fn children() -> Vec<TrackedChild> {
+  let mut children = CHILDREN.lock().expect("Failed to lock children");
+  prune(&mut children);
+  children
+    .iter()
+    .map(|tracked| tracked.info.clone())
+    .collect()
+}
+
+/// Terminates the running child processes spawned with `spawn_relative_command`,
+/// except the protected ones, returning the terminated process ids.
+pub fn kill_children() -> crate::Result<Vec<u32>> {
+  let mut children = CHILDREN.lock().expect("Failed to lock children");
+  prune(&mut children);
+  let mut killed = Vec::new();
+  for tracked in children.iter().filter(|tracked| tracked.info.killable) {
+    // the process can't be reaped while its handle is locked, so its id isn't reused
+    let _child = tracked.child.lock().expect("Failed to lock child");
+    kill(tracked.info.pid)?;
+    killed.push(tracked.info.pid);
+  }
+  // the killed children stay tracked until they exit, so they're reaped by the next prune
+  Ok(killed)
+}
+
+/// Terminates the process with the given id.
+#[cfg(not(windows))]
+fn kill(pid: u32) -> crate::Result<()> {
+  get_output(
+    "kill".to_string(),
+    vec!["-TERM".to_string(), pid.to_string()],
+    Stdio::null(),
+  )?;
+  Ok(())
+}
+
+/// Terminates the process with the given id and its own children.
+#[cfg(windows)]
+fn kill(pid: u32) -> crate::Result<()> {
+  get_output(
+    "taskkill".to_string(),
+    vec![
+      "/PID".to_string(),
+      pid.to_string(),
+      "/T".to_string(),
+      "/F".to_string(),
+    ],
+    Stdio::null(),
+  )?;
+  Ok(())
 }
 
 /// Gets the binary command with the current target triple.
@@ -153,8 +436,16 @@ mod test {
     // generate a cat string
     let cmd = String::from("cat");
 
-    // call spawn_relative_command with cat and the argument test/test.txt on the Stdio.
-    let res = spawn_relative_command(cmd, vec!["test/test.txt".to_string()], Stdio::piped());
+    // call spawn_relative_command with cat and the argument test/test.txt on a piped stdout.
+    let res = spawn_relative_command(
+      cmd,
+      vec!["test/test.txt".to_string()],
+      SpawnStdio {
+        stdout: Stdio::piped(),
+        ..Default::default()
+      },
+      &HashMap::new(),
+    );
 
     // this fails because there is no cat binary in the relative parent folder of this current executing command.
     assert!(res.is_err());
@@ -165,4 +456,66 @@ mod test {
       assert_eq!(s.kind(), std::io::ErrorKind::NotFound);
     }
   }
+
+  #[cfg(not(windows))]
+  #[test]
+  // check that the tracked children are killed, except the protected ones
+  fn check_kill_children() {
+    let spawn_sleep = |killable| {
+      track(
+        Command::new("sleep")
+          .arg("10")
+          .spawn()
+          .expect("failed to spawn sleep"),
+        "sleep".to_string(),
+        killable,
+      )
+    };
+    let mut child = spawn_sleep(true);
+    let mut protected = spawn_sleep(false);
+    let mut exited = track(
+      Command::new("true").spawn().expect("failed to spawn true"),
+      "true".to_string(),
+      true,
+    );
+    exited.wait().expect("failed to wait for true");
+
+    let tracked = children();
+    assert!(tracked.contains(&TrackedChild {
+      pid: child.id(),
+      name: "sleep".to_string(),
+      killable: true,
+    }));
+    assert!(tracked
+      .iter()
+      .any(|c| c.pid == protected.id() && !c.killable));
+    // the exited children are pruned, even though their id could be reused
+    assert!(tracked.iter().all(|c| c.pid != exited.id()));
+
+    let killed = kill_children().expect("failed to kill the children");
+    assert!(killed.contains(&child.id()));
+    assert!(!killed.contains(&protected.id()));
+    assert!(!child.wait().expect("failed to wait for sleep").success());
+
+    protected.kill().expect("failed to kill sleep");
+    protected.wait().expect("failed to wait for sleep");
+    assert!(children().iter().all(|c| c.pid != protected.id()));
+  }
+
+  #[test]
+  // check that spawn_relative_command rejects invalid environment variable names
+  fn check_spawn_cmd_invalid_env() {
+    // create an env map with an empty variable name
+    let mut env = HashMap::new();
+    env.insert(String::new(), "value".to_string());
+
+    // call spawn_relative_command with the invalid env
+    let res = spawn_relative_command("cat".to_string(), vec![], Default::default(), &env);
+
+    // assert that the result is a Command error
+    match res.unwrap_err().downcast_ref::<Error>() {
+      Some(Error::Command(e)) => assert_eq!(*e, "invalid environment variable name ''".to_string()),
+      _ => panic!("expected a Command error"),
+    }
+  }
 }
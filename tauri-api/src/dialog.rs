@@ -2,8 +2,8 @@ use std::path::Path;
 
 pub use nfd::Response;
 use nfd::{open_dialog, DialogType};
-pub use tauri_dialog::DialogSelection;
-use tauri_dialog::{DialogBuilder, DialogButtons, DialogStyle};
+use tauri_dialog::{DialogBuilder, DialogButtons};
+pub use tauri_dialog::{DialogSelection, DialogStyle};
 
 fn open_dialog_internal(
   dialog_type: DialogType,
@@ -34,6 +34,56 @@ pub fn ask(message: impl AsRef<str>, title: impl AsRef<str>) -> DialogSelection
     .show()
 }
 
+/// Checks that there's a display where a dialog can be shown,
+/// so headless environments fail instead of hanging.
+fn ensure_display() -> crate::Result<()> {
+  #[cfg(all(unix, not(target_os = "macos")))]
+  {
+    if std::env::var_os("DISPLAY").is_none() && std::env::var_os("WAYLAND_DISPLAY").is_none() {
+      return Err(crate::Error::Dialog("no display available to show the dialog".into()).into());
+    }
+  }
+  Ok(())
+}
+
+/// Displays a message dialog with an "ok" button.
+/// Fails if there's no display to show it.
+pub fn message(
+  message: impl AsRef<str>,
+  title: impl AsRef<str>,
+  style: DialogStyle,
+) -> crate::Result<()> {
+  ensure_display()?;
+  DialogBuilder::new()
+    .message(message.as_ref())
+    .title(title.as_ref())
+    .style(style)
+    .build()
+    .show();
+  Ok(())
+}
+
+/// Displays a dialog with an "ok" and a "cancel" button, returning whether the user confirmed it.
+/// Fails if there's no display to show it.
+pub fn confirm(
+  message: impl AsRef<str>,
+  title: impl AsRef<str>,
+  style: DialogStyle,
+) -> crate::Result<bool> {
+  ensure_display()?;
+  let selection = DialogBuilder::new()
+    .message(message.as_ref())
+    .title(title.as_ref())
+    .style(style)
+    .buttons(DialogButtons::OkCancel)
+    .build()
+    .show();
+  Ok(match selection {
+    DialogSelection::Ok => true,
+    _ => false,
+  })
+}
+
 /// Open single select file dialog
 pub fn select(
   filter_list: Option<impl AsRef<str>>,
@@ -1,6 +1,8 @@
 use serde::Serialize;
 use std::fs::{self, metadata};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::UNIX_EPOCH;
 use tempfile::{self, tempdir};
 
 /// The result of the `read_dir` function.
@@ -8,11 +10,19 @@ use tempfile::{self, tempdir};
 /// A DiskEntry is either a file or a directory.
 /// The `children` Vec is always `Some` if the entry is a directory.
 #[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct DiskEntry {
   /// The path to this entry.
   pub path: PathBuf,
   /// The name of this entry (file name with extension or directory name)
   pub name: Option<String>,
+  /// Whether this entry is a directory.
+  pub is_dir: bool,
+  /// The size of this entry in bytes. The size of the directories depends on the file system.
+  pub size: u64,
+  /// The last modification time of this entry, in milliseconds since the UNIX epoch,
+  /// if the platform supports it.
+  pub modified: Option<u64>,
   /// The children of this entry if it's a directory.
   #[serde(skip_serializing_if = "Option::is_none")]
   pub children: Option<Vec<DiskEntry>>,
@@ -25,17 +35,32 @@ pub fn is_dir<P: AsRef<Path>>(path: P) -> crate::Result<bool> {
 
 /// Reads a directory. Can perform recursive operations.
 pub fn read_dir<P: AsRef<Path>>(path: P, recursive: bool) -> crate::Result<Vec<DiskEntry>> {
+  read_dir_with_depth(path, if recursive { usize::MAX } else { 0 }, &|_| true)
+}
+
+/// Reads a directory and its subdirectories down to the given depth,
+/// skipping the entries rejected by the `filter`.
+///
+/// A depth of 0 only reads the directory itself, so the children of its subdirectories are empty.
+pub fn read_dir_with_depth<P: AsRef<Path>>(
+  path: P,
+  max_depth: usize,
+  filter: &dyn Fn(&Path) -> bool,
+) -> crate::Result<Vec<DiskEntry>> {
   let mut files_and_dirs: Vec<DiskEntry> = vec![];
   for entry in fs::read_dir(path)? {
     let path = entry?.path();
-    let path_as_string = path.display().to_string();
+    if !filter(&path) {
+      continue;
+    }
 
-    if let Ok(flag) = is_dir(&path_as_string) {
+    if let Ok(metadata) = metadata(&path) {
+      let flag = metadata.is_dir();
       files_and_dirs.push(DiskEntry {
         path: path.clone(),
         children: if flag {
-          Some(if recursive {
-            read_dir(&path_as_string, true)?
+          Some(if max_depth > 0 {
+            read_dir_with_depth(&path, max_depth - 1, filter)?
           } else {
             vec![]
           })
@@ -46,12 +71,54 @@ pub fn read_dir<P: AsRef<Path>>(path: P, recursive: bool) -> crate::Result<Vec<D
           .file_name()
           .map(|name| name.to_string_lossy())
           .map(|name| name.to_string()),
+        is_dir: flag,
+        size: metadata.len(),
+        modified: metadata
+          .modified()
+          .ok()
+          .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+          .map(|duration| duration.as_millis() as u64),
       });
     }
   }
   Result::Ok(files_and_dirs)
 }
 
+/// Computes the total size in bytes of the files in a directory tree.
+///
+/// The tree is walked one directory at a time, so its entries aren't loaded in memory.
+/// The symbolic links aren't followed, so they can't lead the walk out of the tree.
+/// Fails as soon as `cancelled` is set.
+///
+/// # Example
+/// ```
+/// use std::sync::atomic::AtomicBool;
+/// use tauri_api::dir::size;
+/// let bytes = size("test", &AtomicBool::new(false)).unwrap();
+/// assert!(bytes > 0);
+/// ```
+pub fn size<P: AsRef<Path>>(path: P, cancelled: &AtomicBool) -> crate::Result<u64> {
+  let mut total = 0;
+  let mut pending = vec![path.as_ref().to_path_buf()];
+  while let Some(dir) = pending.pop() {
+    for entry in fs::read_dir(dir)? {
+      if cancelled.load(Ordering::Relaxed) {
+        return Err(
+          crate::Error::File("the directory size computation was cancelled".into()).into(),
+        );
+      }
+      let entry = entry?;
+      let metadata = entry.metadata()?;
+      if metadata.is_dir() {
+        pending.push(entry.path());
+      } else if metadata.is_file() {
+        total += metadata.len();
+      }
+    }
+  }
+  Ok(total)
+}
+
 /// Runs a closure with a temp dir argument.
 pub fn with_temp_dir<F: FnOnce(&tempfile::TempDir) -> ()>(callback: F) -> crate::Result<()> {
   let dir = tempdir()?;
@@ -181,6 +248,49 @@ mod test {
     }
   }
 
+  #[test]
+  // check the read_dir_with_depth function depth limit, filter and metadata
+  fn check_read_dir_with_depth() {
+    let dir = tempdir().unwrap();
+    let nested = dir.path().join("a").join("b");
+    fs::create_dir_all(&nested).unwrap();
+    fs::write(nested.join("file.txt"), "tauri").unwrap();
+    fs::write(dir.path().join("skipped.txt"), "").unwrap();
+
+    let filter = |path: &Path| path.file_name() != Some(OsStr::new("skipped.txt"));
+    let entries = read_dir_with_depth(dir.path(), 1, &filter).unwrap();
+    assert_eq!(entries.len(), 1);
+    let a = &entries[0];
+    assert!(a.is_dir);
+    assert!(a.modified.is_some());
+    let a_children = a.children.as_ref().unwrap();
+    assert_eq!(a_children.len(), 1);
+    // the depth limit is reached, so the children of `b` aren't read
+    assert_eq!(a_children[0].children.as_ref().unwrap().len(), 0);
+
+    let entries = read_dir_with_depth(dir.path(), 2, &filter).unwrap();
+    let b = &entries[0].children.as_ref().unwrap()[0];
+    let file = &b.children.as_ref().unwrap()[0];
+    assert_eq!(file.name, Some("file.txt".to_string()));
+    assert!(!file.is_dir);
+    assert_eq!(file.size, 5);
+    assert!(file.children.is_none());
+  }
+
+  #[test]
+  // check that the file sizes of the whole tree are summed and the cancellation is honored
+  fn check_size() {
+    let dir = tempdir().unwrap();
+    let nested = dir.path().join("a").join("b");
+    fs::create_dir_all(&nested).unwrap();
+    fs::write(dir.path().join("root.txt"), "tauri").unwrap();
+    fs::write(nested.join("file.txt"), "tauri-api").unwrap();
+
+    assert_eq!(size(dir.path(), &AtomicBool::new(false)).unwrap(), 14);
+    assert!(size(dir.path(), &AtomicBool::new(true)).is_err());
+    assert!(size(dir.path().join("missing"), &AtomicBool::new(false)).is_err());
+  }
+
   #[test]
   // test the with_temp_dir function
   fn check_test_dir() {
@@ -0,0 +1,27 @@
+use serde::Serialize;
+use std::path::Path;
+
+/// The space information of a volume.
+#[derive(Debug, Serialize)]
+pub struct DiskSpace {
+  /// The bytes available to the current user.
+  pub free: u64,
+  /// The total size of the volume, in bytes.
+  pub total: u64,
+}
+
+/// Gets the free and total space of the volume containing the given path.
+///
+/// # Example
+/// ```
+/// use tauri_api::disk::space;
+/// let space = space(".").expect("failed to get the disk space");
+/// assert!(space.free <= space.total);
+/// ```
+pub fn space<P: AsRef<Path>>(path: P) -> crate::Result<DiskSpace> {
+  let path = path.as_ref();
+  Ok(DiskSpace {
+    free: fs2::available_space(path)?,
+    total: fs2::total_space(path)?,
+  })
+}
@@ -1,5 +1,7 @@
 mod extract;
 mod file_move;
+#[cfg(feature = "hash-file")]
+mod hash;
 
 use std::fs;
 use std::path::Path;
@@ -8,6 +10,8 @@ use crate::Error;
 
 pub use extract::*;
 pub use file_move::*;
+#[cfg(feature = "hash-file")]
+pub use hash::*;
 
 /// Reads a string file.
 pub fn read_string<P: AsRef<Path>>(file: P) -> crate::Result<String> {
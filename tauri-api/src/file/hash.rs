@@ -0,0 +1,122 @@
+use sha2::Digest;
+use std::fs::File;
+use std::io::{ErrorKind, Read};
+use std::path::Path;
+use std::str::FromStr;
+
+/// The size of the chunks read from the hashed files.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// The file hash algorithms.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HashAlgorithm {
+  /// The SHA-256 algorithm.
+  Sha256,
+  /// The MD5 algorithm. It's not collision resistant, so it should only be used for dedup.
+  Md5,
+  /// The BLAKE3 algorithm.
+  Blake3,
+}
+
+impl FromStr for HashAlgorithm {
+  type Err = anyhow::Error;
+
+  fn from_str(algorithm: &str) -> crate::Result<Self> {
+    match algorithm.to_lowercase().as_str() {
+      "sha256" => Ok(Self::Sha256),
+      "md5" => Ok(Self::Md5),
+      "blake3" => Ok(Self::Blake3),
+      _ => Err(
+        crate::Error::File(format!(
+          "unknown hash algorithm '{}'; expected 'sha256', 'md5' or 'blake3'",
+          algorithm
+        ))
+        .into(),
+      ),
+    }
+  }
+}
+
+/// Computes the hex digest of a file, reading it in chunks so large files aren't buffered.
+///
+/// # Example
+/// ```
+/// use tauri_api::file::{hash, HashAlgorithm};
+/// let digest = hash("test/test.txt", HashAlgorithm::Sha256).unwrap();
+/// assert_eq!(digest.len(), 64);
+/// ```
+pub fn hash<P: AsRef<Path>>(file: P, algorithm: HashAlgorithm) -> crate::Result<String> {
+  let file = file.as_ref();
+  match algorithm {
+    HashAlgorithm::Sha256 => {
+      let mut hasher = sha2::Sha256::new();
+      read_chunks(file, |chunk| hasher.update(chunk))?;
+      Ok(format!("{:x}", hasher.finalize()))
+    }
+    HashAlgorithm::Md5 => {
+      let mut hasher = md5::Md5::new();
+      read_chunks(file, |chunk| hasher.update(chunk))?;
+      Ok(format!("{:x}", hasher.finalize()))
+    }
+    HashAlgorithm::Blake3 => {
+      let mut hasher = blake3::Hasher::new();
+      read_chunks(file, |chunk| {
+        hasher.update(chunk);
+      })?;
+      Ok(hasher.finalize().to_hex().to_string())
+    }
+  }
+}
+
+/// Reads the given file in chunks, calling `on_chunk` with each of them.
+fn read_chunks<F: FnMut(&[u8])>(file: &Path, mut on_chunk: F) -> crate::Result<()> {
+  let mut file =
+    File::open(file).map_err(|err| crate::Error::File(format!("Hash failed: {}", err)))?;
+  let mut buffer = vec![0; CHUNK_SIZE];
+  loop {
+    match file.read(&mut buffer) {
+      Ok(0) => return Ok(()),
+      Ok(read) => on_chunk(&buffer[..read]),
+      Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+      Err(e) => return Err(crate::Error::File(format!("Hash failed: {}", e)).into()),
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  // check the file digests of every algorithm
+  fn check_hash() {
+    assert_eq!(
+      hash("test/test.txt", HashAlgorithm::Sha256).unwrap(),
+      "9183607ccd7aa79f8fae73468d059a76e8b17e3ce594446bf8332fc0cc2838ba"
+    );
+    assert_eq!(
+      hash("test/test.txt", HashAlgorithm::Md5).unwrap(),
+      "6dca4336f5c8ee9e2256b539a1813d21"
+    );
+    let empty = tempfile::NamedTempFile::new().unwrap();
+    assert_eq!(
+      hash(empty.path(), HashAlgorithm::Blake3).unwrap(),
+      "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262"
+    );
+    assert!(hash("test/missing.txt", HashAlgorithm::Sha256).is_err());
+  }
+
+  #[test]
+  // check that the unknown algorithms are rejected
+  fn check_hash_algorithm() {
+    assert_eq!(
+      "SHA256".parse::<HashAlgorithm>().unwrap(),
+      HashAlgorithm::Sha256
+    );
+    assert_eq!(
+      "blake3".parse::<HashAlgorithm>().unwrap(),
+      HashAlgorithm::Blake3
+    );
+    assert!("sha1".parse::<HashAlgorithm>().is_err());
+  }
+}
@@ -0,0 +1,142 @@
+#[cfg(not(target_os = "macos"))]
+use crate::config::get as get_config;
+use std::io::ErrorKind;
+#[cfg(not(target_os = "macos"))]
+use std::process::Command;
+
+/// Registers the app as the "open with" handler of the given file extension for the current user.
+///
+/// On Windows the association is written to the `HKEY_CURRENT_USER` registry classes,
+/// and on Linux a hidden `.desktop` entry and a MIME type are added to the user data directory.
+/// On macOS the file types can only be declared by the bundle `Info.plist`, so this fails.
+///
+/// The extension must be alphanumeric; a leading dot is ignored.
+pub fn register(extension: &str, description: &str) -> crate::Result<()> {
+  let extension = extension.trim_start_matches('.');
+  if extension.is_empty()
+    || !extension
+      .chars()
+      .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+  {
+    return Err(crate::Error::Command(format!("invalid file extension '{}'", extension)).into());
+  }
+  register_extension(extension, description).map_err(|e| {
+    let denied = e
+      .downcast_ref::<std::io::Error>()
+      .map_or(false, |e| e.kind() == ErrorKind::PermissionDenied);
+    if denied {
+      crate::Error::Permission(format!(
+        "not allowed to register the .{} file association",
+        extension
+      ))
+      .into()
+    } else {
+      e
+    }
+  })
+}
+
+/// The identifier of the association, based on the bundle identifier.
+#[cfg(not(target_os = "macos"))]
+fn association_id(extension: &str) -> crate::Result<String> {
+  let identifier = &get_config()?.tauri.bundle.identifier;
+  if identifier.is_empty() {
+    return Err(anyhow::anyhow!(
+      "the `tauri.bundle.identifier` config is required to register file associations"
+    ));
+  }
+  Ok(format!("{}.{}", identifier, extension))
+}
+
+/// Runs the given command, failing with its stderr if it doesn't succeed.
+#[cfg(not(target_os = "macos"))]
+fn run(command: &mut Command) -> crate::Result<()> {
+  let output = command.output()?;
+  if output.status.success() {
+    Ok(())
+  } else {
+    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+    if stderr.to_lowercase().contains("denied") {
+      Err(std::io::Error::new(ErrorKind::PermissionDenied, stderr).into())
+    } else {
+      Err(crate::Error::Command(stderr).into())
+    }
+  }
+}
+
+#[cfg(target_os = "windows")]
+fn register_extension(extension: &str, description: &str) -> crate::Result<()> {
+  let prog_id = association_id(extension)?;
+  let exe = std::env::current_exe()?;
+  let classes = r"HKCU\Software\Classes";
+  let entries = vec![
+    (format!(r"{}\.{}", classes, extension), prog_id.clone()),
+    (format!(r"{}\{}", classes, prog_id), description.to_string()),
+    (
+      format!(r"{}\{}\shell\open\command", classes, prog_id),
+      format!(r#""{}" "%1""#, exe.display()),
+    ),
+  ];
+  for (key, value) in entries {
+    run(Command::new("reg").args(&["add", &key, "/ve", "/d", &value, "/f"]))?;
+  }
+  Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn register_extension(_extension: &str, _description: &str) -> crate::Result<()> {
+  Err(
+    crate::Error::Command(
+      "file associations are declared by the app bundle Info.plist on macOS".to_string(),
+    )
+    .into(),
+  )
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn register_extension(extension: &str, description: &str) -> crate::Result<()> {
+  let id = association_id(extension)?;
+  let mime_type = format!("application/x-{}", extension);
+  let data_dir =
+    dirs::data_dir().ok_or_else(|| anyhow::anyhow!("failed to get the user data directory"))?;
+
+  let mime_dir = data_dir.join("mime");
+  let packages_dir = mime_dir.join("packages");
+  std::fs::create_dir_all(&packages_dir)?;
+  std::fs::write(
+    packages_dir.join(format!("{}.xml", id)),
+    format!(
+      r#"<?xml version="1.0" encoding="UTF-8"?>
+<mime-info xmlns="http://www.freedesktop.org/standards/shared-mime-info">
+  <mime-type type="{mime_type}">
+    <comment>{description}</comment>
+    <glob pattern="*.{extension}"/>
+  </mime-type>
+</mime-info>
+"#,
+      mime_type = mime_type,
+      description = description
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;"),
+      extension = extension
+    ),
+  )?;
+
+  let applications_dir = data_dir.join("applications");
+  std::fs::create_dir_all(&applications_dir)?;
+  let desktop_file = format!("{}.desktop", id);
+  std::fs::write(
+    applications_dir.join(&desktop_file),
+    format!(
+      "[Desktop Entry]\nType=Application\nName={name}\nExec=\"{exe}\" %f\nMimeType={mime_type};\nNoDisplay=true\n",
+      name = description.replace('\n', " "),
+      exe = std::env::current_exe()?.display(),
+      mime_type = mime_type
+    ),
+  )?;
+
+  run(Command::new("update-mime-database").arg(&mime_dir))?;
+  run(Command::new("xdg-mime").args(&["default", &desktop_file, &mime_type]))?;
+  Ok(())
+}
@@ -1,11 +1,42 @@
-use attohttpc::{Method, RequestBuilder};
+use crate::config::ProxyConfig;
+use attohttpc::{Method, ProxySettings, RequestBuilder};
 use http::header::HeaderName;
+use once_cell::sync::Lazy;
 use serde::Deserialize;
 use serde_json::Value;
 use serde_repr::{Deserialize_repr, Serialize_repr};
 use std::collections::HashMap;
 use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
 use std::time::Duration;
+use url::Url;
+
+/// The proxy used by `make_request`, or `None` to use the proxy environment variables.
+static PROXY: Lazy<RwLock<Option<ProxyConfig>>> = Lazy::new(|| RwLock::new(None));
+
+/// Sets the proxy used by `make_request`, or clears it with `None` to use the proxy environment variables.
+pub fn set_proxy(proxy: Option<ProxyConfig>) -> crate::Result<()> {
+  if let Some(proxy) = &proxy {
+    proxy_settings(proxy)?;
+  }
+  *PROXY.write().expect("Failed to lock proxy") = proxy;
+  Ok(())
+}
+
+/// Converts the proxy config to the request proxy settings, validating its URL.
+fn proxy_settings(proxy: &ProxyConfig) -> crate::Result<ProxySettings> {
+  let url =
+    Url::parse(&proxy.url).map_err(|e| crate::Error::Proxy(format!("invalid proxy url: {}", e)))?;
+  let mut builder = ProxySettings::builder()
+    .http_proxy(url.clone())
+    .https_proxy(url);
+  for host in &proxy.bypass {
+    builder = builder.add_no_proxy_host(host);
+  }
+  Ok(builder.build())
+}
 
 #[derive(Serialize_repr, Deserialize_repr, Clone, Debug)]
 #[repr(u16)]
@@ -64,6 +95,12 @@ pub struct HttpRequestOptions {
   pub body_type: Option<BodyType>,
   /// The response type (defaults to Json)
   pub response_type: Option<ResponseType>,
+  /// The path of the file to stream the response body to, instead of reading it in memory.
+  /// It isn't checked against the file system scope, so the callers must validate it.
+  pub save_to: Option<PathBuf>,
+  /// The maximum size of the response body read in memory, in bytes
+  #[serde(skip)]
+  pub max_response_bytes: Option<u64>,
 }
 
 /// The builder for HttpRequestOptions.
@@ -109,6 +146,10 @@ pub struct HttpRequestBuilder {
   pub body_type: Option<BodyType>,
   /// The response type (defaults to Json)
   pub response_type: Option<ResponseType>,
+  /// The path of the file to stream the response body to, instead of reading it in memory
+  pub save_to: Option<PathBuf>,
+  /// The maximum size of the response body read in memory, in bytes
+  pub max_response_bytes: Option<u64>,
 }
 
 impl HttpRequestBuilder {
@@ -128,6 +169,8 @@ impl HttpRequestBuilder {
       allow_compression: None,
       body_type: None,
       response_type: None,
+      save_to: None,
+      max_response_bytes: None,
     }
   }
 
@@ -197,6 +240,18 @@ impl HttpRequestBuilder {
     self
   }
 
+  /// Sets the path of the file to stream the response body to.
+  pub fn save_to(mut self, save_to: impl Into<PathBuf>) -> Self {
+    self.save_to = Some(save_to.into());
+    self
+  }
+
+  /// Sets the maximum size of the response body read in memory.
+  pub fn max_response_bytes(mut self, max_response_bytes: u64) -> Self {
+    self.max_response_bytes = Some(max_response_bytes);
+    self
+  }
+
   /// Builds the HttpRequestOptions.
   pub fn build(self) -> HttpRequestOptions {
     HttpRequestOptions {
@@ -213,6 +268,8 @@ impl HttpRequestBuilder {
       allow_compression: self.allow_compression,
       body_type: self.body_type,
       response_type: self.response_type,
+      save_to: self.save_to,
+      max_response_bytes: self.max_response_bytes,
     }
   }
 }
@@ -221,6 +278,9 @@ impl HttpRequestBuilder {
 ///
 /// The response will be transformed to String,
 /// If reading the response as binary, the byte array will be serialized using serde_json
+///
+/// If `save_to` is set, the response body is streamed to that file and the file path is returned.
+/// Otherwise the body is read in memory and the request fails if it exceeds `max_response_bytes`.
 pub fn make_request(options: HttpRequestOptions) -> crate::Result<Value> {
   let method = Method::from_bytes(options.method.to_uppercase().as_bytes())?;
   let mut builder = RequestBuilder::new(method, options.url);
@@ -254,6 +314,9 @@ pub fn make_request(options: HttpRequestOptions) -> crate::Result<Value> {
   if let Some(allow_compression) = options.allow_compression {
     builder = builder.allow_compression(allow_compression);
   }
+  if let Some(proxy) = &*PROXY.read().expect("Failed to lock proxy") {
+    builder = builder.proxy_settings(proxy_settings(proxy)?);
+  }
   builder = builder
     .danger_accept_invalid_certs(true)
     .danger_accept_invalid_hostnames(true);
@@ -289,14 +352,55 @@ pub fn make_request(options: HttpRequestOptions) -> crate::Result<Value> {
   };
 
   let response = response?;
-  if response.is_success() {
-    let response_data = match options.response_type.unwrap_or(ResponseType::Json) {
+  if !response.is_success() {
+    return Err(crate::Error::Network(response.status()).into());
+  }
+
+  if let Some(path) = options.save_to {
+    let (_, _, mut reader) = response.split();
+    // the body is streamed to a temporary file that replaces the target once it's complete,
+    // so a failed download doesn't leave a partial file or truncate an existing one;
+    // the temporary file is deleted when it's dropped on error
+    let dir = match path.parent() {
+      Some(parent) if parent != Path::new("") => parent,
+      _ => Path::new("."),
+    };
+    let mut file = tempfile::NamedTempFile::new_in(dir)?;
+    io::copy(&mut reader, &mut file)?;
+    file.persist(&path).map_err(|e| e.error)?;
+    return Ok(Value::String(path.to_string_lossy().to_string()));
+  }
+
+  let response_type = options.response_type.unwrap_or(ResponseType::Json);
+  let response_data = if let Some(max_response_bytes) = options.max_response_bytes {
+    let too_large = || crate::Error::ResponseSize(max_response_bytes);
+    let content_length = response
+      .headers()
+      .get(http::header::CONTENT_LENGTH)
+      .and_then(|length| length.to_str().ok())
+      .and_then(|length| length.parse::<u64>().ok());
+    if content_length.map_or(false, |length| length > max_response_bytes) {
+      return Err(too_large().into());
+    }
+    let (_, _, reader) = response.split();
+    let mut bytes = Vec::new();
+    reader
+      .take(max_response_bytes + 1)
+      .read_to_end(&mut bytes)?;
+    if bytes.len() as u64 > max_response_bytes {
+      return Err(too_large().into());
+    }
+    match response_type {
+      ResponseType::Json => serde_json::from_slice::<Value>(&bytes)?,
+      ResponseType::Text => Value::String(String::from_utf8_lossy(&bytes).to_string()),
+      ResponseType::Binary => Value::String(serde_json::to_string(&bytes)?),
+    }
+  } else {
+    match response_type {
       ResponseType::Json => response.json::<Value>()?,
       ResponseType::Text => Value::String(response.text()?),
       ResponseType::Binary => Value::String(serde_json::to_string(&response.bytes()?)?),
-    };
-    Ok(response_data)
-  } else {
-    Err(crate::Error::Network(response.status()).into())
-  }
+    }
+  };
+  Ok(response_data)
 }
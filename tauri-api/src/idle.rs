@@ -0,0 +1,236 @@
+use once_cell::sync::Lazy;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// The active sleep inhibitor, released when it's dropped.
+static INHIBITOR: Lazy<Mutex<Option<Inhibitor>>> = Lazy::new(|| Mutex::new(None));
+
+/// Gets how long it's been since the last user input, i.e. keyboard or mouse.
+///
+/// # Example
+/// ```no_run
+/// use tauri_api::idle::idle_time;
+/// let idle = idle_time().expect("failed to get the idle time");
+/// if idle.as_secs() > 300 {
+///   println!("locking the app");
+/// }
+/// ```
+pub fn idle_time() -> crate::Result<Duration> {
+  #[cfg(target_os = "macos")]
+  {
+    let output = command_output("ioreg", &["-c", "IOHIDSystem", "-d", "4"])?;
+    ioreg_idle_time(&output)
+      .ok_or_else(|| crate::Error::Command("failed to parse the ioreg output".into()).into())
+  }
+  #[cfg(target_os = "linux")]
+  {
+    // the X11 tools don't see the Wayland input, so the GNOME idle monitor is tried first
+    let mutter = command_output(
+      "gdbus",
+      &[
+        "call",
+        "--session",
+        "--dest",
+        "org.gnome.Mutter.IdleMonitor",
+        "--object-path",
+        "/org/gnome/Mutter/IdleMonitor/Core",
+        "--method",
+        "org.gnome.Mutter.IdleMonitor.GetIdletime",
+      ],
+    )
+    .ok()
+    .and_then(|output| gdbus_idle_time(&output));
+    if let Some(idle) = mutter {
+      return Ok(idle);
+    }
+    let output = command_output("xprintidle", &[]).map_err(|_| {
+      crate::Error::Command(
+        "the idle time needs the GNOME idle monitor or the xprintidle tool".into(),
+      )
+    })?;
+    output
+      .trim()
+      .parse::<u64>()
+      .map(Duration::from_millis)
+      .map_err(|_| crate::Error::Command("failed to parse the xprintidle output".into()).into())
+  }
+  #[cfg(windows)]
+  {
+    use winapi::um::sysinfoapi::GetTickCount;
+    use winapi::um::winuser::{GetLastInputInfo, LASTINPUTINFO};
+    let mut info = LASTINPUTINFO {
+      cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+      dwTime: 0,
+    };
+    if unsafe { GetLastInputInfo(&mut info) } == 0 {
+      return Err(crate::Error::Command("failed to get the last input time".into()).into());
+    }
+    // the tick count wraps around every 49.7 days, like the last input time
+    let idle = unsafe { GetTickCount() }.wrapping_sub(info.dwTime);
+    Ok(Duration::from_millis(u64::from(idle)))
+  }
+  #[cfg(not(any(target_os = "macos", target_os = "linux", windows)))]
+  {
+    Err(anyhow::anyhow!(
+      "the idle time is not supported on this platform"
+    ))
+  }
+}
+
+/// Prevents the system from sleeping, e.g. during a media playback or a long task,
+/// or allows it again. The system can sleep again when the app exits, even if it's not allowed explicitly.
+///
+/// The display can still turn off; only the system sleep is prevented.
+///
+/// # Example
+/// ```no_run
+/// use tauri_api::idle::prevent_sleep;
+/// prevent_sleep(true).expect("failed to prevent the sleep");
+/// // run the long task
+/// prevent_sleep(false).expect("failed to allow the sleep");
+/// ```
+pub fn prevent_sleep(enabled: bool) -> crate::Result<()> {
+  let mut inhibitor = INHIBITOR.lock().expect("Failed to lock sleep inhibitor");
+  if !enabled {
+    // dropping the inhibitor releases it
+    *inhibitor = None;
+    return Ok(());
+  }
+  if inhibitor.is_none() {
+    *inhibitor = Some(Inhibitor::acquire()?);
+  }
+  Ok(())
+}
+
+/// A system sleep inhibitor.
+///
+/// On Linux and macOS it's a child process holding the inhibitor, which also exits with the app.
+/// On Windows it's a thread holding the execution state, which is reset when the thread ends.
+struct Inhibitor {
+  #[cfg(not(windows))]
+  child: std::process::Child,
+  #[cfg(windows)]
+  _stop: std::sync::mpsc::Sender<()>,
+}
+
+impl Inhibitor {
+  #[cfg(target_os = "linux")]
+  fn acquire() -> crate::Result<Self> {
+    let pid = std::process::id().to_string();
+    let child = std::process::Command::new("systemd-inhibit")
+      .args(&[
+        "--what=sleep:idle",
+        "--who=tauri",
+        "--why=The app prevents the system sleep",
+        "--mode=block",
+        "tail",
+        "--pid",
+        &pid,
+        "-f",
+        "/dev/null",
+      ])
+      .spawn()
+      .map_err(|e| crate::Error::Command(format!("failed to run systemd-inhibit: {}", e)))?;
+    Ok(Self { child })
+  }
+
+  #[cfg(target_os = "macos")]
+  fn acquire() -> crate::Result<Self> {
+    let pid = std::process::id().to_string();
+    let child = std::process::Command::new("caffeinate")
+      .args(&["-i", "-w", &pid])
+      .spawn()
+      .map_err(|e| crate::Error::Command(format!("failed to run caffeinate: {}", e)))?;
+    Ok(Self { child })
+  }
+
+  #[cfg(windows)]
+  fn acquire() -> crate::Result<Self> {
+    use winapi::um::winbase::SetThreadExecutionState;
+    use winapi::um::winnt::{ES_CONTINUOUS, ES_SYSTEM_REQUIRED};
+    let (stop, stopped) = std::sync::mpsc::channel::<()>();
+    std::thread::spawn(move || {
+      unsafe { SetThreadExecutionState(ES_CONTINUOUS | ES_SYSTEM_REQUIRED) };
+      // blocks until the inhibitor is dropped, closing the channel
+      let _ = stopped.recv();
+      unsafe { SetThreadExecutionState(ES_CONTINUOUS) };
+    });
+    Ok(Self { _stop: stop })
+  }
+
+  #[cfg(not(any(target_os = "macos", target_os = "linux", windows)))]
+  fn acquire() -> crate::Result<Self> {
+    Err(anyhow::anyhow!(
+      "preventing the sleep is not supported on this platform"
+    ))
+  }
+}
+
+#[cfg(not(windows))]
+impl Drop for Inhibitor {
+  fn drop(&mut self) {
+    let _ = self.child.kill();
+    let _ = self.child.wait();
+  }
+}
+
+/// Runs the given command, returning its stdout.
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn command_output(program: &str, args: &[&str]) -> crate::Result<String> {
+  let output = std::process::Command::new(program).args(args).output()?;
+  if output.status.success() {
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+  } else {
+    Err(crate::Error::Command(format!("failed to run {}", program)).into())
+  }
+}
+
+/// Parses the `HIDIdleTime` of the `ioreg -c IOHIDSystem` output, in nanoseconds,
+/// e.g. `    | |   "HIDIdleTime" = 1234567890`.
+#[cfg(any(target_os = "macos", test))]
+fn ioreg_idle_time(output: &str) -> Option<Duration> {
+  output
+    .lines()
+    .find(|line| line.contains("\"HIDIdleTime\""))
+    .and_then(|line| line.split('=').nth(1))
+    .and_then(|nanos| nanos.trim().parse::<u64>().ok())
+    .map(Duration::from_nanos)
+}
+
+/// Parses the GNOME idle monitor `GetIdletime` reply, in milliseconds, e.g. `(uint64 12345,)`.
+#[cfg(any(target_os = "linux", test))]
+fn gdbus_idle_time(output: &str) -> Option<Duration> {
+  output
+    .trim()
+    .trim_start_matches('(')
+    .trim_end_matches(')')
+    .trim_end_matches(',')
+    .trim_start_matches("uint64")
+    .trim()
+    .parse::<u64>()
+    .ok()
+    .map(Duration::from_millis)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  // check that the ioreg output is parsed
+  fn check_ioreg_idle_time() {
+    let output = "+-o IOHIDSystem  <class IOHIDSystem>\n    | |   \"HIDIdleTime\" = 2500000000\n    | |   \"HIDParameters\" = {}\n";
+    assert_eq!(ioreg_idle_time(output), Some(Duration::from_millis(2500)));
+    assert_eq!(ioreg_idle_time("+-o IOHIDSystem\n"), None);
+  }
+
+  #[test]
+  // check that the GNOME idle monitor reply is parsed
+  fn check_gdbus_idle_time() {
+    assert_eq!(
+      gdbus_idle_time("(uint64 12345,)\n"),
+      Some(Duration::from_millis(12345))
+    );
+    assert_eq!(gdbus_idle_time("Error: no such service\n"), None);
+  }
+}
@@ -0,0 +1,93 @@
+use serde::Serialize;
+use std::path::Path;
+
+/// Why the app isn't running from an installed location.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NotInstalledReason {
+  /// The app was moved to a random read-only location by the macOS Gatekeeper path randomization.
+  Translocated,
+  /// The app is running from a mounted disk image.
+  DiskImage,
+  /// The app is running from a temporary directory, e.g. an extracted archive.
+  Temporary,
+}
+
+/// The install location of the running app.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallLocation {
+  /// Whether the app is running from an installed location, so it can update itself.
+  pub installed: bool,
+  /// Why the app isn't installed, if it isn't.
+  pub reason: Option<NotInstalledReason>,
+  /// The path to the app executable.
+  pub path: String,
+}
+
+/// Checks whether the app is running from an installed location,
+/// or from a disk image, a translocated or a temporary path where it shouldn't update itself.
+///
+/// # Example
+/// ```
+/// use tauri_api::install::location;
+/// let location = location().unwrap();
+/// if !location.installed {
+///   println!("move the app to the Applications folder to update it");
+/// }
+/// ```
+pub fn location() -> crate::Result<InstallLocation> {
+  let exe = std::env::current_exe()?;
+  let reason = not_installed_reason(&exe, &std::env::temp_dir());
+  Ok(InstallLocation {
+    installed: reason.is_none(),
+    reason,
+    path: exe.display().to_string(),
+  })
+}
+
+/// Gets why the given executable path isn't an installed location, if it isn't.
+fn not_installed_reason(exe: &Path, temp_dir: &Path) -> Option<NotInstalledReason> {
+  let exe = exe.canonicalize().unwrap_or_else(|_| exe.to_path_buf());
+  let temp_dir = temp_dir
+    .canonicalize()
+    .unwrap_or_else(|_| temp_dir.to_path_buf());
+  if exe
+    .components()
+    .any(|component| component.as_os_str() == "AppTranslocation")
+  {
+    Some(NotInstalledReason::Translocated)
+  } else if cfg!(target_os = "macos") && exe.starts_with("/Volumes") {
+    Some(NotInstalledReason::DiskImage)
+  } else if exe.starts_with(&temp_dir) {
+    Some(NotInstalledReason::Temporary)
+  } else {
+    None
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  // check that the install location issues are detected
+  fn check_not_installed_reason() {
+    let temp_dir = Path::new("/tmp/tauri-install-test");
+    assert_eq!(
+      not_installed_reason(
+        Path::new("/private/var/folders/x/AppTranslocation/1234/d/App.app/Contents/MacOS/app"),
+        temp_dir
+      ),
+      Some(NotInstalledReason::Translocated)
+    );
+    assert_eq!(
+      not_installed_reason(Path::new("/tmp/tauri-install-test/app/app"), temp_dir),
+      Some(NotInstalledReason::Temporary)
+    );
+    assert_eq!(
+      not_installed_reason(Path::new("/usr/bin/app"), temp_dir),
+      None
+    );
+  }
+}
@@ -13,14 +13,32 @@ pub mod config;
 pub mod dialog;
 /// The Dir module is a helper for file system directory management.
 pub mod dir;
+/// The disk module allows you to query the space of a volume.
+pub mod disk;
 /// The File API module contains helpers to perform file operations.
 pub mod file;
+/// The file association API module allows you to register the app as the handler of a file type.
+pub mod file_association;
 /// The HTTP request API.
 pub mod http;
+/// The idle module allows you to get the user idle time and to prevent the system sleep.
+pub mod idle;
+/// The install module allows you to check whether the app is running from an installed location.
+pub mod install;
+/// The locale module allows you to get the OS locale and the languages preferred by the user.
+pub mod locale;
+/// The network module allows you to get the network connectivity state.
+pub mod network;
 /// The file system path operations API.
 pub mod path;
+/// The power module allows you to query the battery and power source state.
+pub mod power;
 /// The RPC module includes utilities to send messages to the JS layer of the webview.
 pub mod rpc;
+/// The scope module allows you to check if a path is allowed by a list of paths.
+pub mod scope;
+/// The shell API module allows you to open files with the default application of the OS.
+pub mod shell;
 /// TCP ports access API.
 pub mod tcp;
 /// The semver API.
@@ -37,6 +55,10 @@ extern crate clap;
 #[cfg(feature = "notification")]
 pub mod notification;
 
+/// The file system watcher API module.
+#[cfg(feature = "watch")]
+pub mod watch;
+
 pub use tauri_utils::*;
 
 /// Alias for a Result with error type anyhow::Error.
@@ -64,4 +86,13 @@ pub enum Error {
   /// The network error.
   #[error("Network Error:{0}")]
   Network(attohttpc::StatusCode),
+  /// The permission error, e.g. when an operation requires elevation.
+  #[error("Permission Error:{0}")]
+  Permission(String),
+  /// The HTTP response size error.
+  #[error("Response Size Error:the response is larger than the {0} bytes limit")]
+  ResponseSize(u64),
+  /// The proxy configuration error.
+  #[error("Proxy Error:{0}")]
+  Proxy(String),
 }
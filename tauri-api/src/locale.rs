@@ -0,0 +1,206 @@
+/// Gets the OS locale as a BCP 47 language tag, e.g. `en-US`.
+///
+/// # Example
+/// ```no_run
+/// use tauri_api::locale::locale;
+/// let locale = locale().expect("failed to get the locale");
+/// println!("formatting the dates for {}", locale);
+/// ```
+pub fn locale() -> crate::Result<String> {
+  #[cfg(target_os = "macos")]
+  let locale = defaults_string(&command_output("defaults", &["read", "-g", "AppleLocale"])?);
+  #[cfg(windows)]
+  let locale = first_line(&command_output(
+    "powershell",
+    &[
+      "-NoProfile",
+      "-NonInteractive",
+      "-Command",
+      "(Get-Culture).Name",
+    ],
+  )?);
+  #[cfg(not(any(target_os = "macos", windows)))]
+  let locale = env_locale(&|var| std::env::var(var).ok());
+  locale
+    .map(|locale| to_language_tag(&locale))
+    .ok_or_else(|| crate::Error::Command("unable to determine the locale".into()).into())
+}
+
+/// Gets the languages preferred by the user, in order of preference, as BCP 47 language tags.
+/// Falls back to the OS locale if the platform has no preferred languages list.
+///
+/// # Example
+/// ```no_run
+/// use tauri_api::locale::preferred_languages;
+/// let languages = preferred_languages().expect("failed to get the preferred languages");
+/// println!("loading the {} translations", languages[0]);
+/// ```
+pub fn preferred_languages() -> crate::Result<Vec<String>> {
+  #[cfg(target_os = "macos")]
+  let languages = defaults_array(&command_output(
+    "defaults",
+    &["read", "-g", "AppleLanguages"],
+  )?);
+  #[cfg(windows)]
+  let languages = lines(&command_output(
+    "powershell",
+    &[
+      "-NoProfile",
+      "-NonInteractive",
+      "-Command",
+      "(Get-WinUserLanguageList).LanguageTag",
+    ],
+  )?);
+  #[cfg(not(any(target_os = "macos", windows)))]
+  let languages = env_languages(&|var| std::env::var(var).ok());
+  let mut tags: Vec<String> = Vec::new();
+  for language in languages.iter().map(|language| to_language_tag(language)) {
+    if !tags.contains(&language) {
+      tags.push(language);
+    }
+  }
+  if tags.is_empty() {
+    tags.push(locale()?);
+  }
+  Ok(tags)
+}
+
+/// Runs the given command, returning its stdout.
+#[cfg(any(target_os = "macos", windows))]
+fn command_output(program: &str, args: &[&str]) -> crate::Result<String> {
+  let output = std::process::Command::new(program).args(args).output()?;
+  if output.status.success() {
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+  } else {
+    Err(crate::Error::Command(format!("failed to run {}", program)).into())
+  }
+}
+
+/// Converts a POSIX locale, e.g. `en_US.UTF-8@euro`, to a BCP 47 language tag, e.g. `en-US`.
+fn to_language_tag(locale: &str) -> String {
+  locale
+    .split(|c| c == '.' || c == '@')
+    .next()
+    .unwrap_or("")
+    .trim()
+    .replace('_', "-")
+}
+
+/// Checks whether the given POSIX locale names a language, i.e. it's not the `C` or `POSIX` locale.
+#[cfg(any(not(any(target_os = "macos", windows)), test))]
+fn is_language(locale: &str) -> bool {
+  let tag = to_language_tag(locale);
+  !tag.is_empty() && tag != "C" && tag != "POSIX"
+}
+
+/// Gets the locale from the POSIX environment variables, in order of precedence.
+#[cfg(any(not(any(target_os = "macos", windows)), test))]
+fn env_locale(var: &dyn Fn(&str) -> Option<String>) -> Option<String> {
+  ["LC_ALL", "LC_MESSAGES", "LANG"]
+    .iter()
+    .filter_map(|name| var(name))
+    .find(|locale| is_language(locale))
+}
+
+/// Gets the preferred languages from the `LANGUAGE` list, e.g. `fr_FR:fr:en`, and the locale.
+#[cfg(any(not(any(target_os = "macos", windows)), test))]
+fn env_languages(var: &dyn Fn(&str) -> Option<String>) -> Vec<String> {
+  let mut languages: Vec<String> = var("LANGUAGE")
+    .unwrap_or_default()
+    .split(':')
+    .filter(|language| is_language(language))
+    .map(String::from)
+    .collect();
+  // the LANGUAGE list is ignored by the programs if the locale is C
+  match env_locale(var) {
+    Some(locale) => languages.push(locale),
+    None => languages.clear(),
+  }
+  languages
+}
+
+/// Parses a `defaults read` string value, e.g. `en_US@currency=EUR`.
+#[cfg(any(target_os = "macos", test))]
+fn defaults_string(output: &str) -> Option<String> {
+  Some(output.trim().to_string()).filter(|value| !value.is_empty())
+}
+
+/// Parses a `defaults read` array value, e.g. `(\n    "en-US",\n    fr\n)`.
+#[cfg(any(target_os = "macos", test))]
+fn defaults_array(output: &str) -> Vec<String> {
+  output
+    .trim()
+    .trim_start_matches('(')
+    .trim_end_matches(')')
+    .split(',')
+    .map(|value| value.trim().trim_matches('"').to_string())
+    .filter(|value| !value.is_empty())
+    .collect()
+}
+
+/// Gets the first non-empty line of the given command output.
+#[cfg(windows)]
+fn first_line(output: &str) -> Option<String> {
+  lines(output).into_iter().next()
+}
+
+/// Gets the non-empty lines of the given command output.
+#[cfg(windows)]
+fn lines(output: &str) -> Vec<String> {
+  output
+    .lines()
+    .map(|line| line.trim().to_string())
+    .filter(|line| !line.is_empty())
+    .collect()
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use std::collections::HashMap;
+
+  #[test]
+  // check that the POSIX locales are converted to language tags
+  fn check_to_language_tag() {
+    assert_eq!(to_language_tag("en_US.UTF-8"), "en-US");
+    assert_eq!(to_language_tag("de_DE@euro"), "de-DE");
+    assert_eq!(to_language_tag("pt-BR"), "pt-BR");
+  }
+
+  #[test]
+  // check that the POSIX environment variables are read in order of precedence
+  fn check_env_languages() {
+    let env: HashMap<&str, &str> = vec![
+      ("LANG", "en_US.UTF-8"),
+      ("LC_MESSAGES", "C"),
+      ("LANGUAGE", "fr_FR:fr"),
+    ]
+    .into_iter()
+    .collect();
+    let var = |name: &str| env.get(name).map(|value| value.to_string());
+    assert_eq!(env_locale(&var), Some("en_US.UTF-8".to_string()));
+    assert_eq!(env_languages(&var), vec!["fr_FR", "fr", "en_US.UTF-8"]);
+
+    let var = |name: &str| match name {
+      "LANG" => Some("C".to_string()),
+      "LANGUAGE" => Some("fr".to_string()),
+      _ => None,
+    };
+    assert_eq!(env_locale(&var), None);
+    assert!(env_languages(&var).is_empty());
+  }
+
+  #[test]
+  // check that the defaults output is parsed
+  fn check_defaults() {
+    assert_eq!(
+      defaults_string("en_US@currency=EUR\n"),
+      Some("en_US@currency=EUR".to_string())
+    );
+    assert_eq!(defaults_string("\n"), None);
+    assert_eq!(
+      defaults_array("(\n    \"en-US\",\n    fr,\n    \"zh-Hans-CN\"\n)\n"),
+      vec!["en-US", "fr", "zh-Hans-CN"]
+    );
+  }
+}
@@ -0,0 +1,193 @@
+use serde::Serialize;
+use std::net::{IpAddr, UdpSocket};
+#[cfg(any(target_os = "linux", test))]
+use std::path::Path;
+
+/// The public addresses used to find a route to the internet.
+/// Connecting a UDP socket only looks up the route, so nothing is sent to them.
+const REACHABILITY_ADDRESSES: [(&str, &str); 2] = [
+  ("0.0.0.0:0", "8.8.8.8:53"),
+  ("[::]:0", "[2001:4860:4860::8888]:53"),
+];
+
+/// The network connectivity state.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkStatus {
+  /// Whether the device has a route to the internet through a connected interface.
+  pub online: bool,
+  /// The local address of the route to the internet, if any.
+  pub local_address: Option<String>,
+  /// The names of the connected interfaces, excluding the loopback ones.
+  pub interfaces: Vec<String>,
+}
+
+/// Gets the network connectivity state.
+///
+/// It's a lightweight reachability check: the device is online if a connected interface
+/// has a route to the internet. It doesn't detect the captive portals or the filtered networks.
+///
+/// # Example
+/// ```no_run
+/// use tauri_api::network::status;
+/// let status = status().expect("failed to get the network status");
+/// if !status.online {
+///   println!("pausing the sync");
+/// }
+/// ```
+pub fn status() -> crate::Result<NetworkStatus> {
+  #[cfg(target_os = "linux")]
+  let interfaces = sysfs_interfaces(Path::new("/sys/class/net"));
+  #[cfg(target_os = "macos")]
+  let interfaces = ifconfig_interfaces(&command_output("ifconfig", &[])?);
+  #[cfg(windows)]
+  let interfaces = lines(&command_output(
+    "powershell",
+    &[
+      "-NoProfile",
+      "-NonInteractive",
+      "-Command",
+      "Get-NetAdapter | Where-Object Status -eq 'Up' | ForEach-Object Name",
+    ],
+  )?);
+  #[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+  let interfaces: Vec<String> = Vec::new();
+  let local_address = if interfaces.is_empty() {
+    None
+  } else {
+    route_address().map(|address| address.to_string())
+  };
+  Ok(NetworkStatus {
+    online: local_address.is_some(),
+    local_address,
+    interfaces,
+  })
+}
+
+/// Gets the local address of the route to the internet, trying IPv4 and then IPv6.
+fn route_address() -> Option<IpAddr> {
+  REACHABILITY_ADDRESSES.iter().find_map(|(local, remote)| {
+    let socket = UdpSocket::bind(local).ok()?;
+    socket.connect(remote).ok()?;
+    socket
+      .local_addr()
+      .ok()
+      .map(|address| address.ip())
+      .filter(|address| !address.is_unspecified() && !address.is_loopback())
+  })
+}
+
+/// Runs the given command, returning its stdout.
+#[cfg(any(target_os = "macos", windows))]
+fn command_output(program: &str, args: &[&str]) -> crate::Result<String> {
+  let output = std::process::Command::new(program).args(args).output()?;
+  if output.status.success() {
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+  } else {
+    Err(crate::Error::Command(format!("failed to run {}", program)).into())
+  }
+}
+
+/// Reads the connected interfaces from the `/sys/class/net` devices.
+#[cfg(any(target_os = "linux", test))]
+fn sysfs_interfaces(net_dir: &Path) -> Vec<String> {
+  let read = |device: &Path, file: &str| {
+    std::fs::read_to_string(device.join(file))
+      .map(|value| value.trim().to_string())
+      .unwrap_or_default()
+  };
+  let mut interfaces: Vec<String> = match std::fs::read_dir(net_dir) {
+    Ok(entries) => entries
+      .filter_map(|entry| entry.ok())
+      .map(|entry| entry.path())
+      .filter(|device| {
+        // 772 is the loopback device type; the tunnels report an unknown state with a carrier
+        read(device, "type") != "772"
+          && match read(device, "operstate").as_str() {
+            "up" => true,
+            "unknown" => read(device, "carrier") == "1",
+            _ => false,
+          }
+      })
+      .filter_map(|device| {
+        device
+          .file_name()
+          .map(|name| name.to_string_lossy().to_string())
+      })
+      .collect(),
+    Err(_) => Vec::new(),
+  };
+  interfaces.sort();
+  interfaces
+}
+
+/// Parses the connected interfaces of the `ifconfig` output, e.g.
+/// `en0: flags=8863<UP,BROADCAST,SMART,RUNNING,SIMPLEX,MULTICAST> mtu 1500\n\tstatus: active`.
+#[cfg(any(target_os = "macos", test))]
+fn ifconfig_interfaces(output: &str) -> Vec<String> {
+  let mut interfaces = Vec::new();
+  let mut current: Option<String> = None;
+  for line in output.lines() {
+    if !line.starts_with(|c: char| c.is_whitespace()) {
+      interfaces.extend(current.take());
+      let mut parts = line.splitn(2, ": ");
+      let name = parts.next().unwrap_or("");
+      let flags = parts
+        .next()
+        .and_then(|rest| rest.split('<').nth(1))
+        .and_then(|flags| flags.split('>').next())
+        .unwrap_or("");
+      let flags: Vec<&str> = flags.split(',').collect();
+      if flags.contains(&"UP") && flags.contains(&"RUNNING") && !flags.contains(&"LOOPBACK") {
+        current = Some(name.to_string());
+      }
+    } else if line.trim() == "status: inactive" {
+      current = None;
+    }
+  }
+  interfaces.extend(current);
+  interfaces
+}
+
+/// Gets the non-empty lines of the given command output.
+#[cfg(windows)]
+fn lines(output: &str) -> Vec<String> {
+  output
+    .lines()
+    .map(|line| line.trim().to_string())
+    .filter(|line| !line.is_empty())
+    .collect()
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use std::fs;
+
+  #[test]
+  // check that the connected interfaces are read and the loopback is skipped
+  fn check_sysfs_interfaces() {
+    let dir = tempfile::tempdir().unwrap();
+    assert!(sysfs_interfaces(dir.path()).is_empty());
+
+    let device = |name: &str, kind: &str, state: &str, carrier: &str| {
+      let device = dir.path().join(name);
+      fs::create_dir(&device).unwrap();
+      fs::write(device.join("type"), format!("{}\n", kind)).unwrap();
+      fs::write(device.join("operstate"), format!("{}\n", state)).unwrap();
+      fs::write(device.join("carrier"), format!("{}\n", carrier)).unwrap();
+    };
+    device("lo", "772", "unknown", "1");
+    device("wlan0", "1", "up", "1");
+    device("eth0", "1", "down", "0");
+    device("wg0", "65534", "unknown", "1");
+    assert_eq!(sysfs_interfaces(dir.path()), vec!["wg0", "wlan0"]);
+  }
+
+  #[test]
+  // check that the ifconfig output is parsed
+  fn check_ifconfig_interfaces() {
+    let output = "lo0: flags=8049<UP,LOOPBACK,RUNNING,MULTICAST> mtu 16384\n\tinet 127.0.0.1 netmask 0xff000000\nen0: flags=8863<UP,BROADCAST,SMART,RUNNING,SIMPLEX,MULTICAST> mtu 1500\n\tinet 192.168.1.2 netmask 0xffffff00\n\tstatus: active\nen1: flags=8863<UP,BROADCAST,SMART,RUNNING,SIMPLEX,MULTICAST> mtu 1500\n\tstatus: inactive\nutun0: flags=8051<UP,POINTOPOINT,RUNNING,MULTICAST> mtu 1380\n";
+    assert_eq!(ifconfig_interfaces(output), vec!["en0", "utun0"]);
+  }
+}
@@ -188,6 +188,19 @@ fn app_name() -> crate::Result<String> {
   Ok(app_name.to_string())
 }
 
+/// Returns the path to the suggested directory for your app cache files.
+/// Resolves to ${CACHE_DIR}/${APP_NAME}
+pub fn app_cache_dir() -> Option<PathBuf> {
+  dirs::cache_dir().and_then(|mut dir| {
+    if let Ok(app_name) = app_name() {
+      dir.push(app_name);
+      Some(dir)
+    } else {
+      None
+    }
+  })
+}
+
 /// Returns the path to the suggested directory for your app config files.
 pub fn app_dir() -> Option<PathBuf> {
   dirs::config_dir().and_then(|mut dir| {
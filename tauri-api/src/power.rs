@@ -0,0 +1,248 @@
+use serde::Serialize;
+#[cfg(any(target_os = "linux", test))]
+use std::path::Path;
+
+/// The battery and power source state.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PowerState {
+  /// Whether the device is running on battery, i.e. the AC adapter isn't connected.
+  pub on_battery: bool,
+  /// The battery charge percentage, or `None` if the device has no battery.
+  pub battery_percent: Option<u8>,
+  /// Whether the battery is charging.
+  pub charging: bool,
+}
+
+impl PowerState {
+  /// The state of the devices without a battery, always on AC power.
+  fn no_battery() -> Self {
+    Self {
+      on_battery: false,
+      battery_percent: None,
+      charging: false,
+    }
+  }
+}
+
+/// Gets the battery and power source state.
+///
+/// The devices without a battery, e.g. most desktops, are reported on AC power
+/// with no battery percentage.
+///
+/// # Example
+/// ```no_run
+/// use tauri_api::power::state;
+/// let state = state().expect("failed to get the power state");
+/// if state.on_battery {
+///   println!("reducing the background work");
+/// }
+/// ```
+pub fn state() -> crate::Result<PowerState> {
+  #[cfg(target_os = "linux")]
+  return Ok(sysfs_state(Path::new("/sys/class/power_supply")));
+  #[cfg(target_os = "macos")]
+  return Ok(pmset_state(&command_output("pmset", &["-g", "batt"])?));
+  #[cfg(windows)]
+  return Ok(wmic_state(&command_output(
+    "WMIC",
+    &[
+      "Path",
+      "Win32_Battery",
+      "Get",
+      "BatteryStatus,EstimatedChargeRemaining",
+      "/format:list",
+    ],
+  )?));
+  #[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+  Err(anyhow::anyhow!(
+    "the power state is not supported on this platform"
+  ))
+}
+
+/// Runs the given command, returning its stdout.
+#[cfg(any(target_os = "macos", windows))]
+fn command_output(program: &str, args: &[&str]) -> crate::Result<String> {
+  let output = std::process::Command::new(program).args(args).output()?;
+  if output.status.success() {
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+  } else {
+    Err(crate::Error::Command(format!("failed to run {}", program)).into())
+  }
+}
+
+/// Reads the power state from the `/sys/class/power_supply` devices.
+#[cfg(any(target_os = "linux", test))]
+fn sysfs_state(power_supply_dir: &Path) -> PowerState {
+  let read = |device: &Path, file: &str| {
+    std::fs::read_to_string(device.join(file))
+      .map(|value| value.trim().to_string())
+      .unwrap_or_default()
+  };
+  let mut ac_online = None;
+  let mut battery = None;
+  if let Ok(entries) = std::fs::read_dir(power_supply_dir) {
+    for entry in entries.filter_map(|entry| entry.ok()) {
+      let device = entry.path();
+      match read(&device, "type").as_str() {
+        "Mains" => ac_online = Some(ac_online.unwrap_or(false) || read(&device, "online") == "1"),
+        "Battery" if battery.is_none() => {
+          battery = Some((
+            read(&device, "capacity").parse::<u8>().ok(),
+            read(&device, "status"),
+          ))
+        }
+        _ => {}
+      }
+    }
+  }
+  match battery {
+    Some((percent, status)) => PowerState {
+      on_battery: match ac_online {
+        Some(online) => !online,
+        None => status == "Discharging",
+      },
+      battery_percent: percent,
+      charging: status == "Charging",
+    },
+    None => PowerState::no_battery(),
+  }
+}
+
+/// Parses the `pmset -g batt` output, e.g.
+/// `Now drawing from 'Battery Power'\n -InternalBattery-0 (id=1234)\t85%; discharging; 3:45 remaining`.
+#[cfg(any(target_os = "macos", test))]
+fn pmset_state(output: &str) -> PowerState {
+  let battery = output
+    .lines()
+    .find(|line| line.contains("InternalBattery"))
+    .and_then(|line| line.split('\t').nth(1));
+  match battery {
+    Some(battery) => {
+      let mut fields = battery.split(';').map(|field| field.trim());
+      let percent = fields
+        .next()
+        .and_then(|percent| percent.trim_end_matches('%').parse::<u8>().ok());
+      let status = fields.next().unwrap_or("");
+      PowerState {
+        on_battery: output.contains("'Battery Power'"),
+        battery_percent: percent,
+        charging: status == "charging",
+      }
+    }
+    None => PowerState::no_battery(),
+  }
+}
+
+/// Parses the `WMIC Path Win32_Battery Get BatteryStatus,EstimatedChargeRemaining /format:list` output,
+/// e.g. `BatteryStatus=2\r\nEstimatedChargeRemaining=85`.
+#[cfg(any(windows, test))]
+fn wmic_state(output: &str) -> PowerState {
+  let value = |key: &str| {
+    output
+      .lines()
+      .find(|line| line.trim().starts_with(&format!("{}=", key)))
+      .and_then(|line| line.trim()[key.len() + 1..].parse::<u16>().ok())
+  };
+  match value("BatteryStatus") {
+    Some(status) => PowerState {
+      // 1 is discharging, 4 and 5 are discharging on low and critical charge, 6 to 9 are charging
+      on_battery: match status {
+        1 | 4 | 5 => true,
+        _ => false,
+      },
+      battery_percent: value("EstimatedChargeRemaining").map(|percent| percent.min(100) as u8),
+      charging: (6..=9).contains(&status),
+    },
+    None => PowerState::no_battery(),
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use std::fs;
+
+  #[test]
+  // check that the battery and AC adapter devices are read
+  fn check_sysfs_state() {
+    let dir = tempfile::tempdir().unwrap();
+    assert_eq!(sysfs_state(dir.path()), PowerState::no_battery());
+
+    let ac = dir.path().join("AC");
+    fs::create_dir(&ac).unwrap();
+    fs::write(ac.join("type"), "Mains\n").unwrap();
+    fs::write(ac.join("online"), "0\n").unwrap();
+    let battery = dir.path().join("BAT0");
+    fs::create_dir(&battery).unwrap();
+    fs::write(battery.join("type"), "Battery\n").unwrap();
+    fs::write(battery.join("capacity"), "42\n").unwrap();
+    fs::write(battery.join("status"), "Discharging\n").unwrap();
+    assert_eq!(
+      sysfs_state(dir.path()),
+      PowerState {
+        on_battery: true,
+        battery_percent: Some(42),
+        charging: false,
+      }
+    );
+
+    fs::write(ac.join("online"), "1\n").unwrap();
+    fs::write(battery.join("status"), "Charging\n").unwrap();
+    assert_eq!(
+      sysfs_state(dir.path()),
+      PowerState {
+        on_battery: false,
+        battery_percent: Some(42),
+        charging: true,
+      }
+    );
+  }
+
+  #[test]
+  // check that the pmset output is parsed
+  fn check_pmset_state() {
+    assert_eq!(
+      pmset_state("Now drawing from 'Battery Power'\n -InternalBattery-0 (id=1234)\t85%; discharging; 3:45 remaining present: true\n"),
+      PowerState {
+        on_battery: true,
+        battery_percent: Some(85),
+        charging: false,
+      }
+    );
+    assert_eq!(
+      pmset_state("Now drawing from 'AC Power'\n -InternalBattery-0 (id=1234)\t60%; charging; 1:10 remaining present: true\n"),
+      PowerState {
+        on_battery: false,
+        battery_percent: Some(60),
+        charging: true,
+      }
+    );
+    assert_eq!(
+      pmset_state("Now drawing from 'AC Power'\n"),
+      PowerState::no_battery()
+    );
+  }
+
+  #[test]
+  // check that the WMIC output is parsed
+  fn check_wmic_state() {
+    assert_eq!(
+      wmic_state("\r\n\r\nBatteryStatus=1\r\nEstimatedChargeRemaining=73\r\n\r\n"),
+      PowerState {
+        on_battery: true,
+        battery_percent: Some(73),
+        charging: false,
+      }
+    );
+    assert_eq!(
+      wmic_state("BatteryStatus=6\r\nEstimatedChargeRemaining=20\r\n"),
+      PowerState {
+        on_battery: false,
+        battery_percent: Some(20),
+        charging: true,
+      }
+    );
+    assert_eq!(wmic_state(""), PowerState::no_battery());
+  }
+}
@@ -32,38 +32,101 @@ pub fn format_callback<T: Into<JsonValue>, S: AsRef<str> + Display>(
   format!(r#"window["{}"]({})"#, function_name, arg.into().to_string())
 }
 
+/// The status of an invoke response.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InvokeStatus {
+  /// The command succeeded.
+  Ok,
+  /// The command failed.
+  Error,
+}
+
+/// The envelope of every invoke response, so the frontend handles the results uniformly.
+/// The `data` is set when the command succeeds and the `error` when it fails.
+#[derive(Debug, Serialize)]
+pub struct InvokeResponse<T, E> {
+  /// The response status.
+  pub status: InvokeStatus,
+  /// The command result.
+  pub data: Option<T>,
+  /// The command error.
+  pub error: Option<E>,
+}
+
+impl<T, E> From<Result<T, E>> for InvokeResponse<T, E> {
+  fn from(result: Result<T, E>) -> Self {
+    match result {
+      Ok(data) => Self {
+        status: InvokeStatus::Ok,
+        data: Some(data),
+        error: None,
+      },
+      Err(error) => Self {
+        status: InvokeStatus::Error,
+        data: None,
+        error: Some(error),
+      },
+    }
+  }
+}
+
 /// Formats a Result type to its Promise response.
 /// Useful for Promises handling.
-/// If the Result `is_ok()`, the callback will be the `success_callback` function name and the argument will be the Ok value.
-/// If the Result `is_err()`, the callback will be the `error_callback` function name and the argument will be the Err value.
+/// If the Result `is_ok()`, the callback will be the `success_callback` function name and the argument will be the `{ status: "ok", data }` envelope.
+/// If the Result `is_err()`, the callback will be the `error_callback` function name and the argument will be the `{ status: "error", error }` envelope.
 ///
 /// * `result` the Result to check
 /// * `success_callback` the function name of the Ok callback. Usually the `resolve` of the JS Promise.
 /// * `error_callback` the function name of the Err callback. Usually the `reject` of the JS Promise.
 ///
-/// Note that the callback strings are automatically generated by the `promisified` helper.
+/// Note that the callback strings are automatically generated by the `promisified` helper,
+/// which unwraps the envelope.
 ///
 /// # Examples
 /// ```
 /// use tauri_api::rpc::format_callback_result;
 /// let res: Result<u8, &str> = Ok(5);
 /// let cb = format_callback_result(res, "success_cb".to_string(), "error_cb".to_string()).expect("failed to format");
-/// assert_eq!(cb, r#"window["success_cb"](5)"#);
+/// assert_eq!(cb, r#"window["success_cb"]({"status":"ok","data":5,"error":null})"#);
 ///
 /// let res: Result<&str, &str> = Err("error message here");
 /// let cb = format_callback_result(res, "success_cb".to_string(), "error_cb".to_string()).expect("failed to format");
-/// assert_eq!(cb, r#"window["error_cb"]("error message here")"#);
+/// assert_eq!(cb, r#"window["error_cb"]({"status":"error","data":null,"error":"error message here"})"#);
 /// ```
 pub fn format_callback_result<T: Serialize, E: Serialize>(
   result: Result<T, E>,
   success_callback: String,
   error_callback: String,
 ) -> crate::Result<String> {
-  let rpc = match result {
-    Ok(res) => format_callback(success_callback, serde_json::to_value(res)?),
-    Err(err) => format_callback(error_callback, serde_json::to_value(err)?),
+  let function_name = if result.is_ok() {
+    success_callback
+  } else {
+    error_callback
   };
-  Ok(rpc)
+  // serialized directly, so the envelope keys keep their order
+  let response = serde_json::to_string(&InvokeResponse::from(result))?;
+  Ok(format!(r#"window["{}"]({})"#, function_name, response))
+}
+
+/// Formats the error envelope of a failed command, to reject its Promise.
+///
+/// # Examples
+/// ```
+/// use tauri_api::rpc::format_callback_error;
+/// let cb = format_callback_error("error_cb", "'readDir' not whitelisted");
+/// assert_eq!(cb, r#"window["error_cb"]({"status":"error","data":null,"error":"'readDir' not whitelisted"})"#);
+/// ```
+pub fn format_callback_error<S: AsRef<str> + Display, E: Into<JsonValue>>(
+  error_callback: S,
+  error: E,
+) -> String {
+  let response: InvokeResponse<(), JsonValue> = Err(error.into()).into();
+  format!(
+    r#"window["{}"]({})"#,
+    error_callback,
+    serde_json::to_string(&response).expect("failed to serialize the error response")
+  )
 }
 
 #[cfg(test)]
@@ -89,16 +152,25 @@ mod test {
   fn qc_format_res(result: Result<String, String>, c: String, ec: String) -> bool {
     let resp = format_callback_result(result.clone(), c.clone(), ec.clone())
       .expect("failed to format callback result");
-    let (function, value) = match result {
-      Ok(v) => (c, v),
-      Err(e) => (ec, e),
+    let (function, status, data, error) = match result {
+      Ok(v) => (
+        c,
+        "ok",
+        serde_json::Value::String(v),
+        serde_json::Value::Null,
+      ),
+      Err(e) => (
+        ec,
+        "error",
+        serde_json::Value::Null,
+        serde_json::Value::String(e),
+      ),
     };
 
     resp
       == format!(
-        r#"window["{}"]({})"#,
-        function,
-        serde_json::Value::String(value),
+        r#"window["{}"]({{"status":"{}","data":{},"error":{}}})"#,
+        function, status, data, error,
       )
   }
 }
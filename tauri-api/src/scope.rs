@@ -0,0 +1,95 @@
+use once_cell::sync::Lazy;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// The directories the user granted access to during this session.
+static GRANTED: Lazy<Mutex<Vec<PathBuf>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Checks if the given path is one of the scope paths or is inside one of the scope directories.
+///
+/// Both the path and the scope entries are canonicalized before the check,
+/// so symbolic links and `..` components can't be used to escape the scope.
+/// Paths that don't exist are never allowed.
+///
+/// # Example
+/// ```
+/// use tauri_api::scope::is_allowed;
+/// assert!(is_allowed("test/test.txt", &["test"]));
+/// assert!(!is_allowed("test/../Cargo.toml", &["test"]));
+/// ```
+pub fn is_allowed<P: AsRef<Path>, S: AsRef<Path>>(path: P, scope: &[S]) -> bool {
+  let path = match path.as_ref().canonicalize() {
+    Ok(path) => path,
+    Err(_) => return false,
+  };
+  scope
+    .iter()
+    .any(|entry| match entry.as_ref().canonicalize() {
+      Ok(entry) => path.starts_with(entry),
+      Err(_) => false,
+    })
+}
+
+/// Grants access to the given directory for the rest of the session,
+/// returning its canonicalized path.
+///
+/// Fails if the directory doesn't exist.
+pub fn grant<P: AsRef<Path>>(dir: P) -> crate::Result<PathBuf> {
+  let dir = dir.as_ref().canonicalize()?;
+  if !dir.is_dir() {
+    return Err(crate::Error::Path(format!("{} is not a directory", dir.display())).into());
+  }
+  let mut granted = GRANTED.lock().expect("Failed to lock granted scope");
+  if !granted.contains(&dir) {
+    granted.push(dir.clone());
+  }
+  Ok(dir)
+}
+
+/// Gets the directories granted with `grant` during this session.
+pub fn granted() -> Vec<PathBuf> {
+  GRANTED
+    .lock()
+    .expect("Failed to lock granted scope")
+    .clone()
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  // check that paths inside the scope are allowed
+  fn check_allowed_path() {
+    assert!(is_allowed("test/test.txt", &["test"]));
+    assert!(is_allowed("test", &["test"]));
+    assert!(is_allowed("test/test.txt", &["src", "test/test.txt"]));
+  }
+
+  #[test]
+  // check that paths outside the scope are rejected
+  fn check_disallowed_path() {
+    // escaping the scope with a parent dir component
+    assert!(!is_allowed("test/../Cargo.toml", &["test"]));
+    // paths that don't exist
+    assert!(!is_allowed("test/missing.txt", &["test"]));
+    // empty scope
+    let scope: Vec<String> = vec![];
+    assert!(!is_allowed("test/test.txt", &scope));
+  }
+
+  #[test]
+  // check that the granted directories are added once to the session scope
+  fn check_grant() {
+    let dir = grant("test").expect("failed to grant the test dir");
+    grant("test/../test").expect("failed to grant the test dir");
+    assert_eq!(
+      granted().iter().filter(|granted| **granted == dir).count(),
+      1
+    );
+    assert!(is_allowed("test/test.txt", &granted()));
+
+    assert!(grant("test/test.txt").is_err());
+    assert!(grant("test/missing").is_err());
+  }
+}
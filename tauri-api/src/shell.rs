@@ -0,0 +1,25 @@
+use std::path::Path;
+use std::process::Command;
+
+/// Opens the given file or directory with the default application of the OS.
+pub fn open<P: AsRef<Path>>(path: P) -> crate::Result<()> {
+  let path = path.as_ref();
+
+  #[cfg(target_os = "windows")]
+  let status = Command::new("cmd")
+    .args(&["/C", "start", ""])
+    .arg(path)
+    .status()?;
+
+  #[cfg(target_os = "macos")]
+  let status = Command::new("open").arg(path).status()?;
+
+  #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+  let status = Command::new("xdg-open").arg(path).status()?;
+
+  if status.success() {
+    Ok(())
+  } else {
+    Err(crate::Error::Command(format!("failed to open {}", path.display())).into())
+  }
+}
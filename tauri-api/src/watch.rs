@@ -0,0 +1,165 @@
+use notify::{watcher, DebouncedEvent, RecommendedWatcher, RecursiveMode, Watcher};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{mpsc::channel, Mutex};
+use std::time::Duration;
+
+/// How many watchers can be active at the same time.
+pub const MAX_WATCHERS: usize = 32;
+
+/// How long the changes are debounced, so a burst of changes to a path is reported once.
+const DEBOUNCE_INTERVAL: Duration = Duration::from_millis(200);
+
+/// The active watchers, by id.
+static WATCHERS: Lazy<Mutex<HashMap<u32, RecommendedWatcher>>> =
+  Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// The id of the next watcher.
+static NEXT_ID: AtomicU32 = AtomicU32::new(1);
+
+/// The kind of a file change.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ChangeKind {
+  /// The path was created.
+  Create,
+  /// The path contents were written.
+  Write,
+  /// The path permissions or other metadata changed.
+  Metadata,
+  /// The path was removed.
+  Remove,
+  /// The path was renamed.
+  Rename,
+}
+
+/// A change of a watched path.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileChange {
+  /// The id of the watcher that reported the change.
+  pub id: u32,
+  /// The changed path.
+  pub path: PathBuf,
+  /// The kind of the change.
+  pub kind: ChangeKind,
+  /// The new path of the renamed paths.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub to: Option<PathBuf>,
+}
+
+/// Watches the given files or directories, calling `on_change` on a background thread
+/// with the debounced changes. Returns the watcher id, used to stop it with `unwatch`.
+///
+/// Fails if `MAX_WATCHERS` watchers are already active.
+///
+/// # Example
+/// ```no_run
+/// use tauri_api::watch::{watch, unwatch};
+/// let id = watch(vec!["src".into()], true, |change| {
+///   println!("{:?} changed", change.path);
+/// }).expect("failed to watch the src directory");
+/// unwatch(id).expect("failed to stop the watcher");
+/// ```
+pub fn watch<F: Fn(FileChange) + Send + 'static>(
+  paths: Vec<PathBuf>,
+  recursive: bool,
+  on_change: F,
+) -> crate::Result<u32> {
+  let mut watchers = WATCHERS.lock().expect("Failed to lock watchers");
+  if watchers.len() >= MAX_WATCHERS {
+    return Err(
+      crate::Error::File(format!(
+        "only {} watchers can be active at the same time",
+        MAX_WATCHERS
+      ))
+      .into(),
+    );
+  }
+  let (tx, rx) = channel();
+  let mut path_watcher = watcher(tx, DEBOUNCE_INTERVAL)?;
+  let mode = if recursive {
+    RecursiveMode::Recursive
+  } else {
+    RecursiveMode::NonRecursive
+  };
+  for path in &paths {
+    path_watcher.watch(path, mode)?;
+  }
+  let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+  // the channel is closed when the watcher is dropped, ending the thread
+  std::thread::spawn(move || {
+    for event in rx {
+      if let Some(change) = file_change(id, event) {
+        on_change(change);
+      }
+    }
+  });
+  watchers.insert(id, path_watcher);
+  Ok(id)
+}
+
+/// Stops the watcher with the given id.
+pub fn unwatch(id: u32) -> crate::Result<()> {
+  match WATCHERS
+    .lock()
+    .expect("Failed to lock watchers")
+    .remove(&id)
+  {
+    Some(_) => Ok(()),
+    None => Err(crate::Error::File(format!("there's no watcher with the id {}", id)).into()),
+  }
+}
+
+/// Stops all the active watchers.
+pub fn unwatch_all() {
+  WATCHERS.lock().expect("Failed to lock watchers").clear();
+}
+
+/// Converts the debounced event to a file change, skipping the notices and the errors.
+fn file_change(id: u32, event: DebouncedEvent) -> Option<FileChange> {
+  let (path, kind, to) = match event {
+    DebouncedEvent::Create(path) => (path, ChangeKind::Create, None),
+    DebouncedEvent::Write(path) => (path, ChangeKind::Write, None),
+    DebouncedEvent::Chmod(path) => (path, ChangeKind::Metadata, None),
+    DebouncedEvent::Remove(path) => (path, ChangeKind::Remove, None),
+    DebouncedEvent::Rename(from, to) => (from, ChangeKind::Rename, Some(to)),
+    _ => return None,
+  };
+  Some(FileChange { id, path, kind, to })
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  // check that the debounced events are converted and the notices are skipped
+  fn check_file_change() {
+    assert_eq!(
+      file_change(1, DebouncedEvent::Write("a.txt".into())),
+      Some(FileChange {
+        id: 1,
+        path: "a.txt".into(),
+        kind: ChangeKind::Write,
+        to: None,
+      })
+    );
+    assert_eq!(
+      file_change(2, DebouncedEvent::Rename("a.txt".into(), "b.txt".into())),
+      Some(FileChange {
+        id: 2,
+        path: "a.txt".into(),
+        kind: ChangeKind::Rename,
+        to: Some("b.txt".into()),
+      })
+    );
+    assert_eq!(
+      file_change(1, DebouncedEvent::NoticeWrite("a.txt".into())),
+      None
+    );
+  }
+}
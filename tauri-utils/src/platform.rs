@@ -92,3 +92,42 @@ pub fn resource_dir() -> Result<PathBuf> {
     Err(crate::Error::Unknown.into())
   }
 }
+
+/// Gets the OS version, e.g. `10.15.7` on macOS, `10.0.19041` on Windows
+/// or the `/etc/os-release` `VERSION_ID` on Linux.
+///
+/// Returns `None` if the version can't be determined.
+pub fn os_version() -> Option<String> {
+  let version = if cfg!(target_os = "macos") {
+    command_output("sw_vers", &["-productVersion"])
+  } else if cfg!(target_os = "windows") {
+    // `ver` prints e.g. `Microsoft Windows [Version 10.0.19041.572]`
+    command_output("cmd", &["/C", "ver"]).and_then(|output| {
+      output
+        .split("Version ")
+        .nth(1)
+        .map(|version| version.trim_end_matches(']').to_string())
+    })
+  } else {
+    std::fs::read_to_string("/etc/os-release")
+      .ok()
+      .and_then(|os_release| {
+        os_release
+          .lines()
+          .find(|line| line.starts_with("VERSION_ID="))
+          .map(|line| line["VERSION_ID=".len()..].trim_matches('"').to_string())
+      })
+      .or_else(|| command_output("uname", &["-r"]))
+  };
+  version.filter(|version| !version.is_empty())
+}
+
+/// Runs the given command, returning its trimmed stdout if it succeeds.
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+  std::process::Command::new(program)
+    .args(args)
+    .output()
+    .ok()
+    .filter(|output| output.status.success())
+    .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
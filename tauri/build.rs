@@ -9,6 +9,10 @@ use std::{
   path::Path,
 };
 
+/// The size of the inlined index.tauri.html above which a warning is shown, in bytes.
+#[cfg(any(feature = "embedded-server", feature = "no-server"))]
+const INLINED_HTML_WARNING_SIZE: usize = 10 * 1024 * 1024;
+
 #[cfg(any(feature = "embedded-server", feature = "no-server"))]
 pub fn main() -> Result<(), Box<dyn Error>> {
   shared();
@@ -67,6 +71,14 @@ pub fn main() -> Result<(), Box<dyn Error>> {
       let original_index_html_path = Path::new(&dist_path_string).join("index.tauri.html");
       let original_index_html = read_to_string(original_index_html_path)?;
 
+      // on no-server the whole app is inlined in the index.tauri.html by the CLI inliner
+      if cfg!(feature = "no-server") && original_index_html.len() > INLINED_HTML_WARNING_SIZE {
+        println!(
+          "cargo:warning=the inlined index.tauri.html is {:.1} MB; consider using the embedded-server to load the assets",
+          original_index_html.len() as f64 / (1024.0 * 1024.0)
+        );
+      }
+
       write!(index_html_file, "{}", original_index_html)?;
     }
     None => {
@@ -114,13 +126,50 @@ fn setup_env_aliases() {
     remove_dir: { any(all_api, feature = "remove-dir") },
     remove_file: { any(all_api, feature = "remove-file") },
     rename_file: { any(all_api, feature = "rename-file") },
+    disk_space: { any(all_api, feature = "disk-space") },
+    pick_scope_folder: { any(all_api, feature = "pick-scope-folder") },
+    watch: { any(all_api, feature = "watch") },
+    hash_file: { any(all_api, feature = "hash-file") },
+    dir_size: { any(all_api, feature = "dir-size") },
 
     // window
     set_title: { any(all_api, feature = "set-title") },
+    set_spellcheck: { any(all_api, feature = "set-spellcheck") },
+    focus: { any(all_api, feature = "focus") },
+    theme: { any(all_api, feature = "theme") },
+    clear_cache: { any(all_api, feature = "clear-cache") },
+    print: { any(all_api, feature = "print") },
+    set_loading: { any(all_api, feature = "set-loading") },
     open: { any(all_api, feature = "open") },
 
     // process
     execute: { any(all_api, feature = "execute") },
+    shell_open: { any(all_api, feature = "shell-open") },
+    file_association: { any(all_api, feature = "file-association") },
+    is_installed: { any(all_api, feature = "is-installed") },
+    get_platform: { any(all_api, feature = "get-platform") },
+    set_badge: { any(all_api, feature = "set-badge") },
+    cookies: { any(all_api, feature = "cookies") },
+    asset_protocol: { any(all_api, feature = "asset-protocol") },
+    child_processes: { any(all_api, feature = "child-processes") },
+    media_permissions: { any(all_api, feature = "media-permissions") },
+    power_state: { any(all_api, feature = "power-state") },
+    scale_factor: { any(all_api, feature = "scale-factor") },
+    close_confirmation: { any(all_api, feature = "close-confirmation") },
+    system_events: { any(all_api, feature = "system-events") },
+    get_config: { any(all_api, feature = "get-config") },
+    activation_policy: { any(all_api, feature = "activation-policy") },
+    locale: { any(all_api, feature = "locale") },
+    exe_path: { any(all_api, feature = "exe-path") },
+    cwd: { any(all_api, feature = "cwd") },
+    prefetch_assets: { any(all_api, feature = "prefetch-assets") },
+    request_headers: { any(all_api, feature = "request-headers") },
+    visible_on_all_workspaces: { any(all_api, feature = "visible-on-all-workspaces") },
+    install_update: { all(feature = "updater", any(all_api, feature = "install-update")) },
+    startup_metrics: { any(all_api, feature = "startup-metrics") },
+    network_status: { any(all_api, feature = "network-status") },
+    idle_time: { any(all_api, feature = "idle-time") },
+    prevent_sleep: { any(all_api, feature = "prevent-sleep") },
 
     // event
     event: { any(all_api, feature = "event") },
@@ -128,14 +177,22 @@ fn setup_env_aliases() {
     // dialog
     open_dialog: { any(all_api, feature = "open-dialog") },
     save_dialog: { any(all_api, feature = "save-dialog") },
+    message_dialog: { any(all_api, feature = "message-dialog") },
 
     // http
     http_request: { any(all_api, feature = "http-request") },
+    set_proxy: { any(all_api, feature = "set-proxy") },
 
     // cli
     cli: { feature = "cli" },
 
     // notification
     notification: { any(all_api, feature = "notification") },
+
+    // store
+    store: { any(all_api, feature = "store") },
+
+    // logger
+    logger: { any(all_api, feature = "logger") },
   }
 }
@@ -1,9 +1,17 @@
-use web_view::WebView;
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use std::sync::{Arc, Mutex};
+use web_view::{Handle, WebView};
 
 mod runner;
 
 type InvokeHandler = Box<dyn FnMut(&mut WebView<'_, ()>, &str) -> Result<(), String>>;
 type Setup = Box<dyn FnMut(&mut WebView<'_, ()>, String)>;
+type DeferredSetup = Arc<Mutex<Box<dyn FnMut(Handle<()>, String) + Send>>>;
+
+/// The document element attribute holding the token of the surface (splashscreen or main content)
+/// the document was loaded on.
+pub(crate) const SURFACE_TOKEN_ATTRIBUTE: &str = "data-tauri-surface";
 
 /// The application runner.
 pub struct App {
@@ -11,8 +19,12 @@ pub struct App {
   invoke_handler: Option<InvokeHandler>,
   /// The setup callback, invoked when the webview is ready.
   setup: Option<Setup>,
+  /// The setup callback invoked on a background thread when the webview is ready.
+  deferred_setup: Option<DeferredSetup>,
   /// The HTML of the splashscreen to render.
   splashscreen_html: Option<String>,
+  /// The data exposed to the frontend as `window.__TAURI_INIT__`.
+  init_data: Option<JsonValue>,
 }
 
 impl App {
@@ -43,10 +55,49 @@ impl App {
     }
   }
 
+  /// Runs the deferred setup callback on a background thread if defined,
+  /// emitting the `setup-complete` event with the source once it returns.
+  ///
+  /// The window content is hidden until the callback returns,
+  /// so the app isn't shown half-initialized; the splashscreen stays visible.
+  pub(crate) fn run_deferred_setup(
+    &self,
+    webview: &mut WebView<'_, ()>,
+    source: String,
+  ) -> crate::Result<()> {
+    if let Some(ref deferred_setup) = self.deferred_setup {
+      let deferred_setup = deferred_setup.clone();
+      let handle = webview.handle();
+      let hide_content = source != "splashscreen";
+      if hide_content {
+        webview.eval("document.documentElement.style.visibility = 'hidden'")?;
+      }
+      std::thread::spawn(move || {
+        {
+          let mut deferred_setup = deferred_setup
+            .lock()
+            .expect("Failed to lock deferred setup");
+          deferred_setup(handle.clone(), source.clone());
+        }
+        if hide_content {
+          let _ = handle
+            .dispatch(|webview| webview.eval("document.documentElement.style.visibility = ''"));
+        }
+        let _ = crate::event::emit(&handle, "setup-complete", Some(source));
+      });
+    }
+    Ok(())
+  }
+
   /// Returns the splashscreen HTML.
   pub fn splashscreen_html(&self) -> Option<&String> {
     self.splashscreen_html.as_ref()
   }
+
+  /// Returns the data exposed to the frontend as `window.__TAURI_INIT__`.
+  pub(crate) fn init_data(&self) -> Option<&JsonValue> {
+    self.init_data.as_ref()
+  }
 }
 
 /// The App builder.
@@ -56,8 +107,12 @@ pub struct AppBuilder {
   invoke_handler: Option<InvokeHandler>,
   /// The setup callback, invoked when the webview is ready.
   setup: Option<Setup>,
+  /// The setup callback invoked on a background thread when the webview is ready.
+  deferred_setup: Option<DeferredSetup>,
   /// The HTML of the splashscreen to render.
   splashscreen_html: Option<String>,
+  /// The data exposed to the frontend as `window.__TAURI_INIT__`.
+  init_data: Option<JsonValue>,
 }
 
 impl AppBuilder {
@@ -66,7 +121,9 @@ impl AppBuilder {
     Self {
       invoke_handler: None,
       setup: None,
+      deferred_setup: None,
       splashscreen_html: None,
+      init_data: None,
     }
   }
 
@@ -85,18 +142,41 @@ impl AppBuilder {
     self
   }
 
+  /// Defines a setup callback that runs on a background thread,
+  /// so a slow initialization doesn't freeze the window.
+  /// It gets a handle to dispatch work on the webview,
+  /// and the `setup-complete` event is emitted once it returns.
+  ///
+  /// The `setup` callback still runs synchronously first, preserving its ordering guarantees.
+  pub fn deferred_setup<F: FnMut(Handle<()>, String) + Send + 'static>(
+    mut self,
+    deferred_setup: F,
+  ) -> Self {
+    self.deferred_setup = Some(Arc::new(Mutex::new(Box::new(deferred_setup))));
+    self
+  }
+
   /// Defines the splashscreen HTML to render.
   pub fn splashscreen_html(mut self, html: &str) -> Self {
     self.splashscreen_html = Some(html.to_string());
     self
   }
 
+  /// Defines the data exposed to the frontend as `window.__TAURI_INIT__` when the bridge is initialized,
+  /// on the splashscreen and on the main content.
+  pub fn init_data<S: Serialize>(mut self, data: S) -> Self {
+    self.init_data = Some(serde_json::to_value(data).expect("failed to serialize init data"));
+    self
+  }
+
   /// Builds the App.
   pub fn build(self) -> App {
     App {
       invoke_handler: self.invoke_handler,
       setup: self.setup,
+      deferred_setup: self.deferred_setup,
       splashscreen_html: self.splashscreen_html,
+      init_data: self.init_data,
     }
   }
 }
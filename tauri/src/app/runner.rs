@@ -1,10 +1,12 @@
 #[allow(unused_imports)]
 use std::{
   env,
+  ffi::OsString,
   fs::{self, read_to_string},
+  net::{TcpStream, ToSocketAddrs},
   path::Path,
-  process::Stdio,
   thread::spawn,
+  time::{Duration, Instant},
 };
 
 use web_view::{builder, Content, WebView};
@@ -12,12 +14,50 @@ use web_view::{builder, Content, WebView};
 use super::App;
 #[cfg(embedded_server)]
 use crate::api::tcp::{get_available_port, port_is_available};
+use crate::error::{ErrorCategory, ErrorPayload};
 use tauri_api::config::get;
 
+/// How often the tauri.conf.json file is checked for changes in development.
+#[cfg(all(dev, debug_assertions))]
+const CONFIG_WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How often the power state is checked for the `power-changed` event.
+#[cfg(power_state)]
+const POWER_WATCH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How often the wall clock is checked for the `system-resume` event.
+#[cfg(system_events)]
+const SUSPEND_WATCH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How much longer than `SUSPEND_WATCH_INTERVAL` a check must take to be reported as a resume,
+/// so a busy system isn't mistaken for a suspended one.
+#[cfg(system_events)]
+const SUSPEND_THRESHOLD: Duration = Duration::from_secs(10);
+
 /// Main entry point for running the Webview
 pub(crate) fn run(application: &mut App) -> crate::Result<()> {
+  // time the startup phases from here
+  #[cfg(startup_metrics)]
+  crate::startup::start();
+
+  // report the panics before any thread is spawned
+  crate::crash_reporter::install()?;
+
+  // write the webview and Rust logs to the log file, unless the app registered its own logger
+  #[cfg(logger)]
+  let _ = crate::logger::init(log::LevelFilter::Info);
+
+  // load the config before the content setup, so the phases are timed apart
+  #[cfg(startup_metrics)]
+  {
+    get()?;
+    crate::startup::record(crate::startup::Phase::ConfigLoad);
+  }
+
   // setup the content using the config struct depending on the compile target
   let main_content = setup_content()?;
+  #[cfg(startup_metrics)]
+  crate::startup::record(crate::startup::Phase::ContentSetup);
 
   // setup the server url for the embedded-server
   #[cfg(embedded_server)]
@@ -44,71 +84,418 @@ pub(crate) fn run(application: &mut App) -> crate::Result<()> {
       None
     },
   )?;
+  #[cfg(startup_metrics)]
+  crate::startup::record(crate::startup::Phase::WebviewBuild);
 
   // spawn the embedded server on our server url
   #[cfg(embedded_server)]
-  spawn_server(server_url)?;
+  {
+    if !embedded_server_disabled() {
+      if get()?.tauri.embedded_server.prime_assets {
+        crate::server::prime_assets();
+      }
+      spawn_server(server_url)?;
+    }
+  }
+
+  // reload the webview when the dev server restarts
+  #[cfg(dev)]
+  spawn_dev_server_watcher(webview.handle())?;
+
+  // reload the config when tauri.conf.json changes
+  #[cfg(all(dev, debug_assertions))]
+  spawn_config_watcher(webview.handle());
+
+  // emit the `power-changed` event when the AC adapter is connected or disconnected
+  #[cfg(power_state)]
+  spawn_power_watcher(webview.handle());
+
+  // emit the `network-changed` event when the connectivity changes
+  #[cfg(network_status)]
+  spawn_network_watcher(webview.handle())?;
+
+  // emit the `system-resume` event when the system wakes up from sleep
+  #[cfg(system_events)]
+  spawn_suspend_watcher(webview.handle());
 
   // spin up the updater process
   #[cfg(feature = "updater")]
-  spawn_updater()?;
+  spawn_updater(webview.handle())?;
 
   // run the webview
   webview.run()?;
 
+  // stop the file system watchers left by the closed window
+  #[cfg(watch)]
+  tauri_api::watch::unwatch_all();
+
+  // release the sleep inhibitor left by the closed window
+  #[cfg(prevent_sleep)]
+  tauri_api::idle::prevent_sleep(false)?;
+
   Ok(())
 }
 
-// setup content for dev-server
+// setup content for dev-server:
+// the devPath and the fallback paths are tried in order, using the first available one
 #[cfg(dev)]
 fn setup_content() -> crate::Result<Content<String>> {
   let config = get()?;
-  if config.build.dev_path.starts_with("http") {
+  let mut errors = Vec::new();
+  for dev_path in std::iter::once(&config.build.dev_path).chain(&config.build.dev_fallback_paths) {
+    match dev_content(dev_path, Duration::from_millis(config.build.dev_timeout_ms)) {
+      Ok(content) => {
+        if !errors.is_empty() {
+          eprintln!("using the fallback dev path {}", dev_path);
+        }
+        return Ok(content);
+      }
+      Err(e) => {
+        if !config.build.dev_fallback_paths.is_empty() {
+          eprintln!("{}", e);
+        }
+        errors.push(e.to_string());
+      }
+    }
+  }
+  Err(crate::Error::Server(errors.join("; ")).into())
+}
+
+// gets the content of a dev server URL or of a directory containing an index.tauri.html
+#[cfg(dev)]
+fn dev_content(dev_path: &str, timeout: Duration) -> crate::Result<Content<String>> {
+  if dev_path.starts_with("http") {
     #[cfg(windows)]
-    {
-      let exempt_output = std::process::Command::new("CheckNetIsolation")
-        .args(&vec!["LoopbackExempt", "-s"])
-        .output()
-        .expect("failed to read LoopbackExempt -s");
+    exempt_loopback();
+    wait_for_dev_server(dev_path, timeout)?;
+    Ok(Content::Url(dev_path.to_string()))
+  } else {
+    let index_path = Path::new(dev_path).join("index.tauri.html");
+    if !index_path.exists() {
+      return Err(
+        crate::Error::Io(format!(
+          "Couldn't find 'index.tauri.html' inside {}; did you forget to run 'tauri dev'?",
+          dev_path
+        ))
+        .into(),
+      );
+    }
+    let html = read_to_string(index_path)?;
+    check_index_html(&html, dev_path)?;
+    Ok(Content::Html(html))
+  }
+}
+
+// checks that the index.tauri.html isn't empty or truncated, which would show a blank window
+#[cfg(any(dev, test))]
+fn check_index_html(html: &str, dev_dir: &str) -> crate::Result<()> {
+  let lowercase = html.to_lowercase();
+  if html.trim().is_empty() || !(lowercase.contains("<html") || lowercase.contains("<body")) {
+    return Err(
+      crate::Error::Io(format!(
+        "index.tauri.html inside {} is empty — did the build complete?",
+        dev_dir
+      ))
+      .into(),
+    );
+  }
+  Ok(())
+}
+
+// allows the webview to connect to the dev server on localhost
+#[cfg(all(dev, windows))]
+fn exempt_loopback() {
+  let exempt_output = std::process::Command::new("CheckNetIsolation")
+    .args(&vec!["LoopbackExempt", "-s"])
+    .output()
+    .expect("failed to read LoopbackExempt -s");
+
+  if !exempt_output.status.success() {
+    panic!("Failed to execute CheckNetIsolation LoopbackExempt -s");
+  }
+
+  let output_str = String::from_utf8_lossy(&exempt_output.stdout).to_lowercase();
+  if !output_str.contains("win32webviewhost_cw5n1h2txyewy") {
+    println!("Running Loopback command");
+    runas::Command::new("powershell")
+      .args(&vec![
+        "CheckNetIsolation LoopbackExempt -a -n=\"Microsoft.Win32WebViewHost_cw5n1h2txyewy\"",
+      ])
+      .force_prompt(true)
+      .status()
+      .expect("failed to run Loopback command");
+  }
+}
+
+// checks whether the dev server at the given address accepts connections
+#[cfg(dev)]
+fn dev_server_is_up(address: &str, connect_timeout: Duration) -> bool {
+  address
+    .to_socket_addrs()
+    .map(|mut addrs| addrs.any(|addr| TcpStream::connect_timeout(&addr, connect_timeout).is_ok()))
+    .unwrap_or(false)
+}
 
-      if !exempt_output.status.success() {
-        panic!("Failed to execute CheckNetIsolation LoopbackExempt -s");
+// waits for the dev server to accept connections, failing after the given timeout
+#[cfg(dev)]
+fn wait_for_dev_server(url: &str, timeout: Duration) -> crate::Result<()> {
+  let address = dev_server_address(url)?;
+  let start = Instant::now();
+  loop {
+    let connect_timeout = timeout
+      .checked_sub(start.elapsed())
+      .unwrap_or_default()
+      .max(Duration::from_millis(1));
+    if dev_server_is_up(&address, connect_timeout) {
+      return Ok(());
+    }
+    if start.elapsed() >= timeout {
+      return Err(crate::Error::Server(format!(
+        "the dev server at {} did not respond after {}ms; make sure it is running or increase `build.devTimeoutMs`",
+        url,
+        timeout.as_millis()
+      ))
+      .into());
+    }
+    std::thread::sleep(Duration::from_millis(200));
+  }
+}
+
+// pings the dev server and reloads the webview when it comes back up after going down
+#[cfg(dev)]
+fn spawn_dev_server_watcher(handle: crate::Handle<()>) -> crate::Result<()> {
+  let config = get()?;
+  let interval = Duration::from_millis(config.build.dev_reconnect_interval_ms);
+  if !config.build.dev_path.starts_with("http") || interval.as_millis() == 0 {
+    return Ok(());
+  }
+  let url = config.build.dev_path.clone();
+  let address = dev_server_address(&url)?;
+  spawn(move || {
+    let mut down = false;
+    let mut attempts = 0;
+    loop {
+      std::thread::sleep(interval);
+      if dev_server_is_up(&address, interval) {
+        if down {
+          println!("dev server at {} is back up, reloading the webview", url);
+          down = false;
+          attempts = 0;
+          if handle
+            .dispatch(|webview| webview.eval("window.location.reload()"))
+            .is_err()
+          {
+            // the webview was closed
+            break;
+          }
+        }
+      } else {
+        if !down {
+          eprintln!("dev server at {} is down, waiting for it to come back", url);
+          down = true;
+        }
+        attempts += 1;
+        println!(
+          "reconnecting to the dev server at {} (attempt {})",
+          url, attempts
+        );
       }
+    }
+  });
+  Ok(())
+}
 
-      let output_str = String::from_utf8_lossy(&exempt_output.stdout).to_lowercase();
-      if !output_str.contains("win32webviewhost_cw5n1h2txyewy") {
-        println!("Running Loopback command");
-        runas::Command::new("powershell")
-          .args(&vec![
-            "CheckNetIsolation LoopbackExempt -a -n=\"Microsoft.Win32WebViewHost_cw5n1h2txyewy\"",
-          ])
-          .force_prompt(true)
-          .status()
-          .expect("failed to run Loopback command");
+// polls the tauri.conf.json modification time, reloading the config
+// and emitting the `config-changed` event with the new config when it changes
+#[cfg(all(dev, debug_assertions))]
+fn spawn_config_watcher(handle: crate::Handle<()>) {
+  let path = match tauri_api::config::path() {
+    Some(path) => path,
+    None => return,
+  };
+  let modified = |path: &Path| {
+    fs::metadata(path)
+      .and_then(|metadata| metadata.modified())
+      .ok()
+  };
+  spawn(move || {
+    let mut last_modified = modified(&path);
+    loop {
+      std::thread::sleep(CONFIG_WATCH_INTERVAL);
+      let current = modified(&path);
+      if current == last_modified {
+        continue;
+      }
+      last_modified = current;
+      match tauri_api::config::reload() {
+        Ok(config) => {
+          println!("{} changed, reloaded the config", path.display());
+          if crate::event::emit(&handle, "config-changed", Some(config)).is_err() {
+            // the webview was closed
+            break;
+          }
+        }
+        Err(e) => eprintln!("failed to reload {}: {}", path.display(), e),
       }
     }
-    Ok(Content::Url(config.build.dev_path.clone()))
-  } else {
-    let dev_dir = &config.build.dev_path;
-    let dev_path = Path::new(dev_dir).join("index.tauri.html");
-    if !dev_path.exists() {
-      panic!(
-        "Couldn't find 'index.tauri.html' inside {}; did you forget to run 'tauri dev'?",
-        dev_dir
-      );
+  });
+}
+
+// polls the power state, emitting the `power-changed` event with the new state
+// when the AC adapter is connected or disconnected; it's a no-op on the devices without a battery
+#[cfg(power_state)]
+fn spawn_power_watcher(handle: crate::Handle<()>) {
+  let mut last_state = match tauri_api::power::state() {
+    Ok(state) if state.battery_percent.is_some() => state,
+    _ => return,
+  };
+  spawn(move || loop {
+    std::thread::sleep(POWER_WATCH_INTERVAL);
+    let state = match tauri_api::power::state() {
+      Ok(state) => state,
+      Err(e) => {
+        eprintln!("failed to get the power state: {}", e);
+        continue;
+      }
+    };
+    if state.on_battery == last_state.on_battery {
+      last_state = state;
+      continue;
     }
-    Ok(Content::Html(read_to_string(dev_path)?))
+    last_state = state.clone();
+    if crate::event::emit(&handle, "power-changed", Some(state)).is_err() {
+      // the webview was closed
+      break;
+    }
+  });
+}
+
+// polls the network status, emitting the `network-changed` event with the new status
+// when the device goes online or offline, or the connected interfaces change.
+// the poll interval is the `tauri.network.statusPollInterval` config; the monitor is disabled if it's 0
+#[cfg(network_status)]
+fn spawn_network_watcher(handle: crate::Handle<()>) -> crate::Result<()> {
+  let interval = get()?.tauri.network.status_poll_interval;
+  if interval == 0 {
+    return Ok(());
   }
+  let interval = Duration::from_secs(interval);
+  let mut last_status = tauri_api::network::status().ok();
+  spawn(move || loop {
+    std::thread::sleep(interval);
+    let status = match tauri_api::network::status() {
+      Ok(status) => status,
+      Err(e) => {
+        eprintln!("failed to get the network status: {}", e);
+        continue;
+      }
+    };
+    if last_status.as_ref() == Some(&status) {
+      continue;
+    }
+    last_status = Some(status.clone());
+    if crate::event::emit(&handle, "network-changed", Some(status)).is_err() {
+      // the webview was closed
+      break;
+    }
+  });
+  Ok(())
+}
+
+/// The payload of the `system-resume` event.
+#[cfg(system_events)]
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ResumePayload {
+  /// Roughly how long the system was suspended, in milliseconds.
+  suspended_for: u64,
+}
+
+// detects the system sleep by the wall clock jumps: the thread isn't scheduled while the system sleeps,
+// so a check that takes much longer than its interval means the system was suspended in between.
+// the web-view backend doesn't expose the platform suspend and session notifications,
+// so the suspend and the shutdown can't be announced before they happen.
+#[cfg(system_events)]
+fn spawn_suspend_watcher(handle: crate::Handle<()>) {
+  spawn(move || loop {
+    let before = std::time::SystemTime::now();
+    std::thread::sleep(SUSPEND_WATCH_INTERVAL);
+    let elapsed = match before.elapsed() {
+      Ok(elapsed) => elapsed,
+      // the clock was set back
+      Err(_) => continue,
+    };
+    if elapsed < SUSPEND_WATCH_INTERVAL + SUSPEND_THRESHOLD {
+      continue;
+    }
+    let payload = ResumePayload {
+      suspended_for: (elapsed - SUSPEND_WATCH_INTERVAL).as_millis() as u64,
+    };
+    if crate::event::emit(&handle, "system-resume", Some(payload)).is_err() {
+      // the webview was closed
+      break;
+    }
+  });
+}
+
+// gets the host:port address of the dev server url
+#[cfg(any(dev, test))]
+fn dev_server_address(url: &str) -> crate::Result<String> {
+  let (default_port, rest) = if url.starts_with("https://") {
+    (443, &url["https://".len()..])
+  } else if url.starts_with("http://") {
+    (80, &url["http://".len()..])
+  } else {
+    return Err(crate::Error::Config(format!("invalid dev server url {}", url)).into());
+  };
+  let host = rest
+    .split(|c| c == '/' || c == '?' || c == '#')
+    .next()
+    .unwrap_or("");
+  if host.is_empty() {
+    return Err(crate::Error::Config(format!("invalid dev server url {}", url)).into());
+  }
+  let has_port = if host.starts_with('[') {
+    host.contains("]:")
+  } else {
+    host.contains(':')
+  };
+  if has_port {
+    Ok(host.to_string())
+  } else {
+    Ok(format!("{}:{}", host, default_port))
+  }
+}
+
+// whether the embedded server is disabled with the `TAURI_DISABLE_EMBEDDED_SERVER=1` env var,
+// so tests can load the content in-process without binding a TCP port
+#[cfg(embedded_server)]
+fn embedded_server_disabled() -> bool {
+  env::var("TAURI_DISABLE_EMBEDDED_SERVER")
+    .map(|value| value == "1" || value == "true")
+    .unwrap_or(false)
 }
 
 // setup content for embedded server
 #[cfg(embedded_server)]
 fn setup_content() -> crate::Result<Content<String>> {
+  if embedded_server_disabled() {
+    // serve the index.tauri.html in-process; the other assets must be inlined in it
+    let index_path = format!(
+      "{}/index.tauri.html",
+      option_env!("TAURI_DIST_DIR")
+        .expect("tauri apps should be built with the TAURI_DIST_DIR environment variable")
+    );
+    let html = crate::assets::ASSETS
+      .get(&index_path)
+      .map_err(|_| crate::Error::Io(format!("Could not read asset {}", index_path)))?;
+    return Ok(Content::Html(String::from_utf8_lossy(&html).to_string()));
+  }
   let (port, valid) = setup_port()?;
   let url = (if valid {
     setup_server_url(port)
   } else {
-    Err(anyhow::anyhow!("invalid port"))
+    Err(crate::Error::Config("invalid port".to_string()).into())
   })
   .expect("Unable to setup URL");
 
@@ -155,14 +542,24 @@ fn spawn_server(server_url: String) -> crate::Result<()> {
   spawn(move || {
     let server = tiny_http::Server::http(server_url.replace("http://", "").replace("https://", ""))
       .expect("Unable to spawn server");
+    #[cfg(startup_metrics)]
+    crate::startup::record(crate::startup::Phase::ServerBind);
     for request in server.incoming_requests() {
       let url = match request.url() {
         "/" => "/index.tauri.html",
         url => url,
       }
       .to_string();
+      let accept_encoding = request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv("Accept-Encoding"))
+        .map(|header| header.value.as_str().to_string());
       request
-        .respond(crate::server::asset_response(&url))
+        .respond(crate::server::asset_response(
+          &url,
+          accept_encoding.as_deref(),
+        ))
         .expect("unable to setup response");
     }
   });
@@ -170,16 +567,159 @@ fn spawn_server(server_url: String) -> crate::Result<()> {
   Ok(())
 }
 
-// spawn an updater process.
+// spawn an updater process, respawning it on failures up to the `tauri.updater.retries` config
+// and emitting its status on the `updater-status` event.
 #[cfg(feature = "updater")]
-fn spawn_updater() -> crate::Result<()> {
-  spawn(|| {
-    tauri_api::command::spawn_relative_command("updater".to_string(), Vec::new(), Stdio::inherit())
-      .expect("Unable to spawn relative command");
+fn spawn_updater(handle: crate::Handle<()>) -> crate::Result<()> {
+  let updater_config = &get()?.tauri.updater;
+  // updating an app running from a disk image or a temporary path fails,
+  // so the frontend is asked to prompt the user to move the app instead
+  let location = tauri_api::install::location()?;
+  if !location.installed {
+    eprintln!(
+      "the app is not running from an installed location ({}); skipping the updater",
+      location.path
+    );
+    crate::event::emit(
+      &handle,
+      "updater-status",
+      Some(serde_json::json!({ "status": "notInstalled", "location": location })),
+    )?;
+    return Ok(());
+  }
+  if updater_config.check_interval_secs > 0 {
+    spawn_update_checker(
+      handle.clone(),
+      Duration::from_secs(updater_config.check_interval_secs),
+    );
+  }
+  spawn(move || {
+    // the installUpdate API waits for the startup run to finish
+    let _guard = match crate::updater::InstallGuard::acquire() {
+      Some(guard) => guard,
+      None => return,
+    };
+    let mut attempt = 0;
+    loop {
+      let _ = crate::event::emit(
+        &handle,
+        "updater-status",
+        Some(serde_json::json!({ "status": "running", "attempt": attempt })),
+      );
+      // killChildren must not interrupt a self-update
+      let result = tauri_api::command::spawn_protected_command(
+        "updater".to_string(),
+        Vec::new(),
+        Default::default(),
+        &updater_config.env,
+      )
+      .and_then(|mut child| child.wait().map_err(Into::into));
+      let error = match result {
+        Ok(status) if status.success() => {
+          let _ = crate::event::emit(
+            &handle,
+            "updater-status",
+            Some(serde_json::json!({ "status": "done" })),
+          );
+          break;
+        }
+        Ok(status) => format!("the updater exited with {}", status),
+        Err(e) => {
+          // the updater binary couldn't be spawned, so retrying won't help
+          eprintln!("failed to spawn the updater: {}", e);
+          let _ = crate::event::emit(
+            &handle,
+            "updater-status",
+            Some(serde_json::json!({ "status": "failed", "error": e.to_string() })),
+          );
+          break;
+        }
+      };
+      if attempt >= updater_config.retries {
+        eprintln!("{}; giving up after {} retries", error, attempt);
+        let _ = crate::event::emit(
+          &handle,
+          "updater-status",
+          Some(serde_json::json!({ "status": "failed", "error": error })),
+        );
+        break;
+      }
+      let backoff = updater_backoff(updater_config.retry_backoff_ms, attempt);
+      attempt += 1;
+      eprintln!(
+        "{}; retrying in {}ms (retry {} of {})",
+        error,
+        backoff.as_millis(),
+        attempt,
+        updater_config.retries
+      );
+      let _ = crate::event::emit(
+        &handle,
+        "updater-status",
+        Some(serde_json::json!({ "status": "retrying", "error": error, "attempt": attempt })),
+      );
+      std::thread::sleep(backoff);
+    }
   });
   Ok(())
 }
 
+// runs the updater in check mode on the given interval,
+// emitting the `update-available` event with the version it prints when an update is found.
+// The thread stops when the webview is closed.
+#[cfg(feature = "updater")]
+fn spawn_update_checker(handle: crate::Handle<()>, interval: Duration) {
+  spawn(move || loop {
+    std::thread::sleep(interval);
+    let output = get().and_then(|config| {
+      let child = tauri_api::command::spawn_protected_command(
+        "updater".to_string(),
+        vec!["--check".to_string()],
+        tauri_api::command::SpawnStdio {
+          stdout: std::process::Stdio::piped(),
+          ..Default::default()
+        },
+        &config.tauri.updater.env,
+      )?;
+      Ok(child.wait_with_output()?)
+    });
+    // dispatching fails once the webview is closed
+    let is_open = || handle.dispatch(|_webview| Ok(())).is_ok();
+    let open = match output {
+      Ok(output) if output.status.success() => {
+        let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if version.is_empty() {
+          is_open()
+        } else {
+          crate::event::emit(
+            &handle,
+            "update-available",
+            Some(serde_json::json!({ "version": version })),
+          )
+          .is_ok()
+        }
+      }
+      Ok(output) => {
+        eprintln!("the update check exited with {}", output.status);
+        is_open()
+      }
+      Err(e) => {
+        eprintln!("failed to check for updates: {}", e);
+        is_open()
+      }
+    };
+    if !open {
+      break;
+    }
+  });
+}
+
+// gets the delay before the given updater retry, doubling it on every retry
+#[cfg(any(feature = "updater", test))]
+fn updater_backoff(retry_backoff_ms: u64, attempt: u32) -> Duration {
+  Duration::from_millis(retry_backoff_ms.saturating_mul(1u64 << attempt.min(16)))
+}
+
 // build the webview struct
 fn build_webview(
   application: &mut App,
@@ -187,20 +727,72 @@ fn build_webview(
   splashscreen_content: Option<Content<String>>,
 ) -> crate::Result<WebView<'_, ()>> {
   let config = get()?;
+  let mut initial_hash_js = None;
+  let content = match (content, config.build.initial_route.as_deref()) {
+    (content, None) => content,
+    (Content::Url(url), Some(route)) => Content::Url(with_initial_route(&url, route)?),
+    (Content::Html(html), Some(route)) => {
+      // the inlined HTML has no URL to append the route to, so the route is set as the hash
+      check_initial_route(route)?;
+      initial_hash_js = Some(format!(
+        "window.location.hash = {}",
+        serde_json::to_string(route.trim_start_matches('#'))?
+      ));
+      Content::Html(html)
+    }
+  };
   let content_clone = match content {
     Content::Html(ref html) => Content::Html(html.clone()),
     Content::Url(ref url) => Content::Url(url.clone()),
   };
   let debug = cfg!(debug_assertions);
   // get properties from config struct
-  let width = config.tauri.window.width;
-  let height = config.tauri.window.height;
-  let resizable = config.tauri.window.resizable;
+  if let Some(ratio) = config.tauri.window.aspect_ratio {
+    if ratio <= 0.0 {
+      return Err(
+        crate::Error::Config(format!(
+          "tauri.window.aspectRatio must be positive, got {}",
+          ratio
+        ))
+        .into(),
+      );
+    }
+  }
+  let (width, height) = config.tauri.window.size();
+  let resizable = window_resizable(&config.tauri.window);
   let fullscreen = config.tauri.window.fullscreen;
-  let title = config.tauri.window.title.clone().into_boxed_str();
+  let title = config
+    .resolve_title(&config.tauri.window.title)?
+    .into_boxed_str();
+
+  // the init data is set by the init script, when the bridge initializes the document
+  if let Some(data) = application.init_data() {
+    crate::endpoints::set_init_data(data.to_string());
+  }
 
   let has_splashscreen = splashscreen_content.is_some();
-  let mut initialized_splashscreen = false;
+  let inject_bridge = config.build.inject_bridge;
+  // the splashscreen document is marked with a token, so the `__initialized` messages are attributed
+  // to the surface that sent them whatever order they arrive in; the main content isn't marked
+  let splashscreen_token = uuid::Uuid::new_v4().to_string();
+  let splashscreen_content =
+    splashscreen_content.map(|content| mark_surface(content, &splashscreen_token));
+  // the window can lose the focus while the content is swapped, so it's focused again
+  // when the splashscreen is closed and when the main content is initialized
+  let refocus = has_splashscreen && config.tauri.window.splashscreen.refocus;
+  let refocus_js = if refocus { "; window.focus()" } else { "" };
+
+  tauri_api::http::set_proxy(config.tauri.network.proxy.clone())?;
+  let mut previous_env = setup_data_directory(&config.tauri.window.data_directory);
+  previous_env.extend(setup_process_model(config.tauri.window.single_process));
+  previous_env.extend(setup_hardware_acceleration(
+    config.tauri.window.hardware_acceleration,
+  ));
+  previous_env.extend(setup_webview_proxy(&config.tauri.network.proxy));
+  // WebKitGTK spawns its web and network processes lazily, after the webview is built,
+  // and they read the environment when they're spawned. The network process is connected
+  // before the web process runs any script, so the environment is restored on the first message.
+  let mut previous_env = Some(previous_env);
 
   let mut webview = builder()
     .title(Box::leak(title))
@@ -209,42 +801,67 @@ fn build_webview(
     .debug(debug)
     .user_data(())
     .invoke_handler(move |webview, arg| {
-      if arg == r#"{"cmd":"__initialized"}"# {
-        let source = if has_splashscreen && !initialized_splashscreen {
-          initialized_splashscreen = true;
+      if let Some(previous_env) = previous_env.take() {
+        restore_env(previous_env);
+      }
+      if let Some(surface) = initialized_surface(arg) {
+        let source = if has_splashscreen && surface.as_ref() == Some(&splashscreen_token) {
           "splashscreen"
         } else {
           "window-1"
         };
+        if refocus && source == "window-1" {
+          webview.eval("window.focus()")?;
+        }
+        // the metrics are complete once the main content is initialized for the first time
+        #[cfg(startup_metrics)]
+        {
+          if source == "window-1" && crate::startup::record(crate::startup::Phase::Initialized) {
+            crate::event::emit(
+              &webview.handle(),
+              "startup-metrics",
+              Some(crate::startup::metrics()),
+            )?;
+          }
+        }
         application.run_setup(webview, source.to_string());
+        application.run_deferred_setup(webview, source.to_string())?;
+      } else if arg == r#"{"cmd":"injectBridge"}"# {
+        // the command is sent with the raw `window.external.invoke`, since the bridge isn't there yet
+        if inject_bridge {
+          webview.eval(
+            "console.warn('the tauri.js bridge is already injected; set build.injectBridge to false to inject it on demand')",
+          )?;
+        } else {
+          webview.eval(&bridge_js()?)?;
+        }
       } else if arg == r#"{"cmd":"closeSplashscreen"}"# {
-        let content_href = match content_clone {
-          Content::Html(ref html) => html,
-          Content::Url(ref url) => url,
-        };
-        webview.eval(&format!(r#"window.location.href = "{}""#, content_href))?;
+        webview.eval(&format!(
+          "{}{}",
+          close_splashscreen_js(&content_clone)?,
+          refocus_js
+        ))?;
       } else {
         let handler_error;
         if let Err(tauri_handle_error) = crate::endpoints::handle(webview, arg) {
-          let tauri_handle_error_str = tauri_handle_error.to_string();
-          if tauri_handle_error_str.contains("unknown variant") {
+          if tauri_handle_error.to_string().contains("unknown variant") {
             let handled_by_app = application.run_invoke_handler(webview, arg);
             handler_error = if let Err(e) = handled_by_app {
-              Some(e.replace("'", "\\'"))
+              Some(ErrorPayload::new(ErrorCategory::Command, e))
             } else {
               let handled = handled_by_app.expect("failed to check if the invoke was handled");
               if handled {
                 None
               } else {
-                Some(tauri_handle_error_str)
+                Some(ErrorPayload::from(&tauri_handle_error))
               }
             };
           } else {
-            handler_error = Some(tauri_handle_error_str);
+            handler_error = Some(ErrorPayload::from(&tauri_handle_error));
           }
 
-          if let Some(handler_error_message) = handler_error {
-            webview.eval(&get_api_error_message(arg, handler_error_message))?;
+          if let Some(handler_error) = handler_error {
+            webview.eval(&get_api_error_message(arg, handler_error)?)?;
           }
         }
       }
@@ -260,10 +877,18 @@ fn build_webview(
 
   webview.set_fullscreen(fullscreen);
 
-  if has_splashscreen {
-    let env_var = envmnt::get_or("TAURI_DIR", "../dist");
-    let path = Path::new(&env_var);
-    let contents = fs::read_to_string(path.join("/tauri.js"))?;
+  if let Some(background_color) = &config.tauri.window.background_color {
+    webview.set_color(parse_color(background_color)?);
+  }
+
+  if let Some(initial_hash_js) = initial_hash_js {
+    webview
+      .handle()
+      .dispatch(move |_webview| _webview.eval(&initial_hash_js))?;
+  }
+
+  if has_splashscreen && inject_bridge {
+    let contents = bridge_js()?;
     // inject the tauri.js entry point
     webview
       .handle()
@@ -273,15 +898,359 @@ fn build_webview(
   Ok(webview)
 }
 
-// Formats an invoke handler error message to print to console.error
-fn get_api_error_message(arg: &str, handler_error_message: String) -> String {
-  format!(
-    r#"console.error('failed to match a command for {}, {}')"#,
-    arg.replace("'", "\\'"),
-    handler_error_message
+// Reads the tauri.js bridge script.
+fn bridge_js() -> crate::Result<String> {
+  let env_var = envmnt::get_or("TAURI_DIR", "../dist");
+  let path = Path::new(&env_var);
+  Ok(fs::read_to_string(path.join("/tauri.js"))?)
+}
+
+// Checks that the `build.initialRoute` config is a hash or a path route.
+fn check_initial_route(route: &str) -> crate::Result<()> {
+  if route.starts_with('#') || route.starts_with('/') {
+    Ok(())
+  } else {
+    Err(
+      crate::Error::Config(format!(
+        "build.initialRoute must start with '#' or '/', got '{}'",
+        route
+      ))
+      .into(),
+    )
+  }
+}
+
+// Appends the `build.initialRoute` config to the content URL:
+// hash routes replace the URL fragment, and path routes are appended to the URL path.
+fn with_initial_route(url: &str, route: &str) -> crate::Result<String> {
+  check_initial_route(route)?;
+  let base = url.split('#').next().unwrap_or(url);
+  if route.starts_with('#') {
+    Ok(format!("{}{}", base, route))
+  } else {
+    let (path, query) = match base.find('?') {
+      Some(index) => base.split_at(index),
+      None => (base, ""),
+    };
+    Ok(format!("{}{}{}", path.trim_end_matches('/'), route, query))
+  }
+}
+
+// Marks the given HTML content with the surface token read by the `__initialized` message.
+// The mark is on the document element, so it's dropped when the document is replaced.
+fn mark_surface(content: Content<String>, token: &str) -> Content<String> {
+  match content {
+    Content::Html(html) => {
+      let (before, after) = html.split_at(surface_marker_index(&html));
+      Content::Html(format!(
+        "{}<script>document.documentElement.setAttribute('{}', '{}')</script>{}",
+        before,
+        super::SURFACE_TOKEN_ATTRIBUTE,
+        token,
+        after
+      ))
+    }
+    Content::Url(url) => Content::Url(url),
+  }
+}
+
+// Gets the index the surface marker script is inserted at: after the `<head>` tag if there's one,
+// or after the doctype, since any markup before the doctype switches the document to the quirks mode.
+fn surface_marker_index(html: &str) -> usize {
+  // the ASCII lowercasing keeps the byte indices
+  let lowercase = html.to_ascii_lowercase();
+  let tag_end = |start: usize| lowercase[start..].find('>').map(|end| start + end + 1);
+  let head = lowercase
+    .match_indices("<head")
+    .map(|(index, _)| index)
+    .find(|index| {
+      lowercase[index + "<head".len()..]
+        .chars()
+        .next()
+        .map(|c| c == '>' || c.is_ascii_whitespace())
+        .unwrap_or(false)
+    });
+  if let Some(index) = head.and_then(tag_end) {
+    return index;
+  }
+  let doctype = lowercase.len() - lowercase.trim_start().len();
+  if lowercase[doctype..].starts_with("<!doctype") {
+    if let Some(index) = tag_end(doctype) {
+      return index;
+    }
+  }
+  0
+}
+
+// Gets the surface token of the given `__initialized` message, or `None` if it's another message.
+// The token is `Some(None)` for the unmarked surfaces.
+fn initialized_surface(arg: &str) -> Option<Option<String>> {
+  let message: serde_json::Value = serde_json::from_str(arg).ok()?;
+  if message.get("cmd").and_then(|cmd| cmd.as_str()) != Some("__initialized") {
+    return None;
+  }
+  Some(
+    message
+      .get("surface")
+      .and_then(|surface| surface.as_str())
+      .map(String::from),
   )
 }
 
+// The environment variables to restore after the webview is built
+type PreviousEnv = Vec<(&'static str, Option<OsString>)>;
+
+// Points the webview storage to the `tauri.window.dataDirectory` config.
+// WebKitGTK reads the XDG base directories when the webview is built,
+// so the previous environment is returned to be restored afterwards.
+#[cfg(target_os = "linux")]
+fn setup_data_directory(data_directory: &Option<String>) -> PreviousEnv {
+  let data_directory = match data_directory {
+    Some(data_directory) => data_directory,
+    None => return Vec::new(),
+  };
+  let data_directory = match tauri_api::path::resolve_path(
+    data_directory,
+    Some(tauri_api::path::BaseDirectory::App),
+  ) {
+    Ok(path) => path,
+    Err(e) => {
+      eprintln!(
+        "failed to resolve tauri.window.dataDirectory: {}; using the default webview data directory",
+        e
+      );
+      return Vec::new();
+    }
+  };
+
+  let dirs = vec![
+    ("XDG_DATA_HOME", data_directory.join("data")),
+    ("XDG_CACHE_HOME", data_directory.join("cache")),
+  ];
+  for (_, dir) in &dirs {
+    if let Err(e) = fs::create_dir_all(dir) {
+      eprintln!(
+        "failed to create the webview data directory {}: {}; using the default webview data directory",
+        dir.display(),
+        e
+      );
+      return Vec::new();
+    }
+  }
+
+  dirs
+    .into_iter()
+    .map(|(var, dir)| {
+      let previous = env::var_os(var);
+      env::set_var(var, dir);
+      (var, previous)
+    })
+    .collect()
+}
+
+// Points the webview storage to the `tauri.window.dataDirectory` config.
+#[cfg(not(target_os = "linux"))]
+fn setup_data_directory(data_directory: &Option<String>) -> PreviousEnv {
+  if data_directory.is_some() {
+    eprintln!(
+      "tauri.window.dataDirectory isn't supported on this platform; using the default webview data directory"
+    );
+  }
+  Vec::new()
+}
+
+// Applies the `tauri.window.singleProcess` config.
+// WebKitGTK can't run the web content in the app process, so the closest mode is used:
+// the web views share a single web process instead of spawning one each.
+#[cfg(target_os = "linux")]
+fn setup_process_model(single_process: Option<bool>) -> PreviousEnv {
+  match single_process {
+    Some(true) => {
+      eprintln!(
+        "WebKitGTK can't run the web content in the app process; the web views share a single web process instead"
+      );
+      let var = "WEBKIT_USE_SINGLE_WEB_PROCESS";
+      let previous = env::var_os(var);
+      env::set_var(var, "1");
+      vec![(var, previous)]
+    }
+    // a web process per web view is the WebKitGTK default
+    _ => Vec::new(),
+  }
+}
+
+// Applies the `tauri.window.singleProcess` config.
+#[cfg(not(target_os = "linux"))]
+fn setup_process_model(single_process: Option<bool>) -> PreviousEnv {
+  if single_process.is_some() {
+    eprintln!(
+      "tauri.window.singleProcess isn't supported by the webview backend on this platform; using the default process model"
+    );
+  }
+  Vec::new()
+}
+
+// Applies the `tauri.window.hardwareAcceleration` config.
+// WebKitGTK reads the compositing mode when the webview is built, so it can't be changed at runtime.
+#[cfg(target_os = "linux")]
+fn setup_hardware_acceleration(hardware_acceleration: bool) -> PreviousEnv {
+  if hardware_acceleration {
+    return Vec::new();
+  }
+  println!("the hardware acceleration is disabled; the webview uses software rendering");
+  let var = "WEBKIT_DISABLE_COMPOSITING_MODE";
+  let previous = env::var_os(var);
+  env::set_var(var, "1");
+  vec![(var, previous)]
+}
+
+// Applies the `tauri.window.hardwareAcceleration` config.
+#[cfg(not(target_os = "linux"))]
+fn setup_hardware_acceleration(hardware_acceleration: bool) -> PreviousEnv {
+  if !hardware_acceleration {
+    eprintln!(
+      "tauri.window.hardwareAcceleration isn't supported by the webview backend on this platform; using the default rendering"
+    );
+  }
+  Vec::new()
+}
+
+// Points the webview network stack to the `tauri.network.proxy` config.
+// WebKitGTK reads the proxy environment variables when the webview is built,
+// unless the desktop proxy settings override them.
+#[cfg(target_os = "linux")]
+fn setup_webview_proxy(proxy: &Option<tauri_api::config::ProxyConfig>) -> PreviousEnv {
+  let proxy = match proxy {
+    Some(proxy) => proxy,
+    None => return Vec::new(),
+  };
+  let bypass = proxy.bypass.join(",");
+  vec![
+    ("http_proxy", proxy.url.as_str()),
+    ("https_proxy", proxy.url.as_str()),
+    ("no_proxy", bypass.as_str()),
+  ]
+  .into_iter()
+  .map(|(var, value)| {
+    let previous = env::var_os(var);
+    env::set_var(var, value);
+    (var, previous)
+  })
+  .collect()
+}
+
+// Points the webview network stack to the `tauri.network.proxy` config.
+#[cfg(not(target_os = "linux"))]
+fn setup_webview_proxy(proxy: &Option<tauri_api::config::ProxyConfig>) -> PreviousEnv {
+  if proxy.is_some() {
+    eprintln!(
+      "the webview uses the system proxy settings on this platform; tauri.network.proxy only applies to the httpRequest API"
+    );
+  }
+  Vec::new()
+}
+
+// Restores the environment variables changed by `setup_data_directory`, `setup_process_model`,
+// `setup_hardware_acceleration` and `setup_webview_proxy`
+fn restore_env(previous_env: PreviousEnv) {
+  for (var, value) in previous_env {
+    match value {
+      Some(value) => env::set_var(var, value),
+      None => env::remove_var(var),
+    }
+  }
+}
+
+// Gets whether the window is resizable, hiding the maximize button the webview can't toggle
+fn window_resizable(window_config: &tauri_api::config::WindowConfig) -> bool {
+  if !window_config.buttons.show_maximize && window_config.resizable {
+    eprintln!("hiding the maximize button by making the window non-resizable");
+    false
+  } else {
+    window_config.resizable
+  }
+}
+
+// Parses a `#rrggbb`, `#rrggbbaa` or `rgba(r, g, b, a)` color string
+fn parse_color(color: &str) -> crate::Result<(u8, u8, u8, u8)> {
+  let invalid_color = || {
+    anyhow::Error::from(crate::Error::Config(format!(
+      "invalid window background color '{}'",
+      color
+    )))
+  };
+  let color = color.trim();
+  if color.starts_with('#') {
+    let hex = &color[1..];
+    if (hex.len() != 6 && hex.len() != 8) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+      return Err(invalid_color());
+    }
+    let component = |index: usize| u8::from_str_radix(&hex[index..index + 2], 16);
+    let alpha = if hex.len() == 8 { component(6)? } else { 255 };
+    Ok((component(0)?, component(2)?, component(4)?, alpha))
+  } else if color.starts_with("rgba(") && color.ends_with(')') {
+    let components: Vec<&str> = color[5..color.len() - 1]
+      .split(',')
+      .map(|c| c.trim())
+      .collect();
+    if components.len() != 4 {
+      return Err(invalid_color());
+    }
+    let alpha: f64 = components[3].parse().map_err(|_| invalid_color())?;
+    if alpha < 0.0 || alpha > 1.0 {
+      return Err(invalid_color());
+    }
+    Ok((
+      components[0].parse().map_err(|_| invalid_color())?,
+      components[1].parse().map_err(|_| invalid_color())?,
+      components[2].parse().map_err(|_| invalid_color())?,
+      (alpha * 255.0).round() as u8,
+    ))
+  } else {
+    Err(invalid_color())
+  }
+}
+
+// Gets the JS that replaces the splashscreen with the main content:
+// URLs are navigated to, and HTML content replaces the splashscreen document.
+fn close_splashscreen_js(content: &Content<String>) -> crate::Result<String> {
+  Ok(match content {
+    Content::Url(url) => format!("window.location.href = {}", serde_json::to_string(url)?),
+    Content::Html(html) => format!(
+      "document.open(); document.write({}); document.close()",
+      serde_json::to_string(html)?
+    ),
+  })
+}
+
+// Formats an invoke handler error to print to console.error,
+// to emit as a `command-error` event with the `{ category, message, details }` object
+// and to reject the promise of the call with the error envelope, if the call has an `error` callback
+fn get_api_error_message(arg: &str, handler_error: ErrorPayload) -> crate::Result<String> {
+  let error = serde_json::to_string(&handler_error)?;
+  let mut message = format!(
+    r#"console.error('failed to match a command for {}', {})"#,
+    arg.replace("'", "\\'"),
+    error
+  );
+  let error_callback = serde_json::from_str::<serde_json::Value>(arg)
+    .ok()
+    .and_then(|arg| arg.get("error").and_then(|e| e.as_str()).map(String::from));
+  if let Some(error_callback) = error_callback {
+    message.push_str(";");
+    message.push_str(&tauri_api::rpc::format_callback_error(
+      error_callback,
+      serde_json::to_value(&handler_error)?,
+    ));
+  }
+  #[cfg(event)]
+  {
+    message.push_str(";");
+    message.push_str(&crate::endpoints::emit_event_js("command-error", &error));
+  }
+  Ok(message)
+}
+
 #[cfg(test)]
 mod test {
   use proptest::prelude::*;
@@ -307,6 +1276,7 @@ mod test {
     #[cfg(embedded_server)]
     match res {
       Ok(Content::Url(u)) => assert!(u.contains("http://")),
+      Ok(Content::Html(_)) if super::embedded_server_disabled() => {}
       _ => panic!("setup content failed"),
     }
 
@@ -347,6 +1317,151 @@ mod test {
     }
   }
 
+  #[test]
+  fn check_dev_server_address() {
+    assert_eq!(
+      super::dev_server_address("http://localhost:8080/index.html").unwrap(),
+      "localhost:8080"
+    );
+    assert_eq!(
+      super::dev_server_address("https://localhost").unwrap(),
+      "localhost:443"
+    );
+    assert_eq!(
+      super::dev_server_address("http://[::1]/?a=b").unwrap(),
+      "[::1]:80"
+    );
+    assert!(super::dev_server_address("localhost:8080").is_err());
+    assert!(super::dev_server_address("http:///").is_err());
+  }
+
+  #[test]
+  fn check_updater_backoff() {
+    assert_eq!(super::updater_backoff(500, 0).as_millis(), 500);
+    assert_eq!(super::updater_backoff(500, 2).as_millis(), 2000);
+    assert_eq!(
+      super::updater_backoff(u64::MAX, 3).as_millis(),
+      u64::MAX as u128
+    );
+  }
+
+  #[test]
+  fn check_index_html() {
+    assert!(super::check_index_html("<!DOCTYPE html><html><body></body></html>", "dist").is_ok());
+    assert!(super::check_index_html("<BODY><p>app</p></BODY>", "dist").is_ok());
+    assert!(super::check_index_html("", "dist").is_err());
+    assert!(super::check_index_html("  \n", "dist").is_err());
+    assert!(super::check_index_html("<scr", "dist").is_err());
+  }
+
+  #[test]
+  fn check_api_error_message_rejects_the_call() {
+    let payload =
+      || crate::error::ErrorPayload::new(crate::ErrorCategory::Command, "unknown variant `foo`");
+    let message =
+      super::get_api_error_message(r#"{"cmd":"foo","callback":"cb","error":"err"}"#, payload())
+        .unwrap();
+    assert!(message.contains(
+      r#"window["err"]({"status":"error","data":null,"error":{"category":"command","details":null,"message":"unknown variant `foo`"}})"#
+    ));
+    let message = super::get_api_error_message(r#"{"cmd":"foo"}"#, payload()).unwrap();
+    assert!(!message.contains("status"));
+  }
+
+  #[test]
+  fn check_close_splashscreen_js() {
+    assert_eq!(
+      super::close_splashscreen_js(&Content::Url("http://localhost:8080".to_string())).unwrap(),
+      r#"window.location.href = "http://localhost:8080""#
+    );
+    assert_eq!(
+      super::close_splashscreen_js(&Content::Html(r#"<p class="app">app</p>"#.to_string()))
+        .unwrap(),
+      r#"document.open(); document.write("<p class=\"app\">app</p>"); document.close()"#
+    );
+  }
+
+  #[test]
+  // check that the initial route is appended to the content URL
+  fn check_with_initial_route() {
+    assert_eq!(
+      super::with_initial_route("http://localhost:8080", "#/onboarding").unwrap(),
+      "http://localhost:8080#/onboarding"
+    );
+    assert_eq!(
+      super::with_initial_route("http://localhost:8080/#/home", "#/onboarding").unwrap(),
+      "http://localhost:8080/#/onboarding"
+    );
+    assert_eq!(
+      super::with_initial_route("http://localhost:8080/", "/onboarding").unwrap(),
+      "http://localhost:8080/onboarding"
+    );
+    assert_eq!(
+      super::with_initial_route("http://localhost:8080/app?lang=en", "/onboarding").unwrap(),
+      "http://localhost:8080/app/onboarding?lang=en"
+    );
+    assert!(super::with_initial_route("http://localhost:8080", "onboarding").is_err());
+  }
+
+  #[test]
+  // check that the `__initialized` messages are attributed to the surface that sent them
+  fn check_initialized_surface() {
+    match super::mark_surface(Content::Html("<p>splash</p>".to_string()), "token") {
+      Content::Html(html) => assert_eq!(
+        html,
+        "<script>document.documentElement.setAttribute('data-tauri-surface', 'token')</script><p>splash</p>"
+      ),
+      Content::Url(_) => panic!("expected the marked HTML content"),
+    }
+    // the marker must not precede the doctype, which would switch the document to the quirks mode
+    match super::mark_surface(
+      Content::Html("<!DOCTYPE html><html><header></header><p>splash</p></html>".to_string()),
+      "token",
+    ) {
+      Content::Html(html) => assert_eq!(
+        html,
+        "<!DOCTYPE html><script>document.documentElement.setAttribute('data-tauri-surface', 'token')</script><html><header></header><p>splash</p></html>"
+      ),
+      Content::Url(_) => panic!("expected the marked HTML content"),
+    }
+    match super::mark_surface(
+      Content::Html("<!doctype html>\n<html><HEAD lang=\"en\"><title>splash</title></HEAD></html>".to_string()),
+      "token",
+    ) {
+      Content::Html(html) => assert_eq!(
+        html,
+        "<!doctype html>\n<html><HEAD lang=\"en\"><script>document.documentElement.setAttribute('data-tauri-surface', 'token')</script><title>splash</title></HEAD></html>"
+      ),
+      Content::Url(_) => panic!("expected the marked HTML content"),
+    }
+    assert_eq!(
+      super::initialized_surface(r#"{"cmd":"__initialized","surface":"token"}"#),
+      Some(Some("token".to_string()))
+    );
+    assert_eq!(
+      super::initialized_surface(r#"{"cmd":"__initialized","surface":null}"#),
+      Some(None)
+    );
+    assert_eq!(
+      super::initialized_surface(r#"{"cmd":"closeSplashscreen"}"#),
+      None
+    );
+    assert_eq!(super::initialized_surface("not json"), None);
+  }
+
+  #[test]
+  fn check_parse_color() {
+    assert_eq!(super::parse_color("#1e1e1e").unwrap(), (30, 30, 30, 255));
+    assert_eq!(super::parse_color("#FF000080").unwrap(), (255, 0, 0, 128));
+    assert_eq!(
+      super::parse_color("rgba(0, 128, 255, 0.5)").unwrap(),
+      (0, 128, 255, 128)
+    );
+    assert!(super::parse_color("#12345").is_err());
+    assert!(super::parse_color("rgba(0, 0, 300, 1)").is_err());
+    assert!(super::parse_color("blue").is_err());
+  }
+
   #[cfg(embedded_server)]
   #[test]
   fn check_setup_port() {
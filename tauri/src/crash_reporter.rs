@@ -0,0 +1,121 @@
+use std::fs;
+use std::panic::PanicInfo;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri_api::config::get;
+use tauri_api::path::{resolve_path, BaseDirectory};
+
+/// Installs the panic hook writing the crash reports, if `tauri.crashReporter.enabled` is set.
+///
+/// The default hook still runs, so the panic is printed to stderr as usual.
+/// This must run before any other thread is spawned, so every panic is reported.
+pub fn install() -> crate::Result<()> {
+  let config = &get()?.tauri.crash_reporter;
+  if !config.enabled {
+    return Ok(());
+  }
+  let show_dialog = config.dialog;
+  let default_hook = std::panic::take_hook();
+  std::panic::set_hook(Box::new(move |info| {
+    default_hook(info);
+    match write_report(info) {
+      Ok(path) => {
+        eprintln!("the crash report was written to {}", path.display());
+        if show_dialog {
+          offer_report(path);
+        }
+      }
+      Err(e) => eprintln!("failed to write the crash report: {}", e),
+    }
+  }));
+  Ok(())
+}
+
+/// Writes the report of the given panic to the `crashes` folder of the app data directory.
+fn write_report(info: &PanicInfo<'_>) -> crate::Result<PathBuf> {
+  let timestamp = SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|duration| duration.as_millis())
+    .unwrap_or_default();
+  let path = resolve_path(
+    format!("crashes/crash-{}.txt", timestamp),
+    Some(BaseDirectory::App),
+  )?;
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent)?;
+  }
+  let message = if let Some(message) = info.payload().downcast_ref::<&str>() {
+    message.to_string()
+  } else if let Some(message) = info.payload().downcast_ref::<String>() {
+    message.clone()
+  } else {
+    String::from("unknown panic payload")
+  };
+  let location = info
+    .location()
+    .map(|location| location.to_string())
+    .unwrap_or_else(|| String::from("unknown location"));
+  let thread = std::thread::current()
+    .name()
+    .unwrap_or("<unnamed>")
+    .to_string();
+  fs::write(
+    &path,
+    format_report(
+      &message,
+      &location,
+      &thread,
+      &format!("{:?}", backtrace::Backtrace::new()),
+    ),
+  )?;
+  Ok(path)
+}
+
+/// Formats the crash report with the panic details, the app and Tauri versions and the platform.
+fn format_report(message: &str, location: &str, thread: &str, backtrace: &str) -> String {
+  let app_version = get()
+    .ok()
+    .and_then(|config| config.package.version.clone())
+    .unwrap_or_else(|| String::from("unknown"));
+  format!(
+    "panicked at '{message}', {location}\nthread: {thread}\napp version: {app_version}\ntauri version: {tauri_version}\nplatform: {os} {os_version} ({arch})\n\nbacktrace:\n{backtrace}\n",
+    message = message,
+    location = location,
+    thread = thread,
+    app_version = app_version,
+    tauri_version = env!("CARGO_PKG_VERSION"),
+    os = std::env::consts::OS,
+    os_version = tauri_api::platform::os_version().unwrap_or_else(|| String::from("unknown")),
+    arch = std::env::consts::ARCH,
+    backtrace = backtrace
+  )
+}
+
+/// Shows a native dialog offering to open the crash report.
+fn offer_report(path: PathBuf) {
+  let selection = tauri_api::dialog::ask(
+    format!(
+      "The app crashed. A crash report was written to {}.\nDo you want to open it?",
+      path.display()
+    ),
+    "Crash report",
+  );
+  if let tauri_api::dialog::DialogSelection::Yes = selection {
+    if let Err(e) = tauri_api::shell::open(path) {
+      eprintln!("failed to open the crash report: {}", e);
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  #[test]
+  // check that the crash report has the panic details and the versions
+  fn check_format_report() {
+    let report = super::format_report("boom", "src/main.rs:1:1", "main", "0: main");
+    assert!(report.starts_with("panicked at 'boom', src/main.rs:1:1\nthread: main\n"));
+    assert!(report.contains(&format!("tauri version: {}\n", env!("CARGO_PKG_VERSION"))));
+    assert!(report.contains(std::env::consts::OS));
+    assert!(report.ends_with("backtrace:\n0: main\n"));
+  }
+}
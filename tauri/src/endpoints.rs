@@ -3,24 +3,62 @@ mod cmd;
 mod file_system;
 mod init;
 mod salt;
+#[cfg(any(
+  open,
+  focus,
+  request_headers,
+  activation_policy,
+  close_confirmation,
+  scale_factor,
+  theme,
+  set_loading,
+  set_badge,
+  visible_on_all_workspaces,
+  print,
+  cookies,
+  media_permissions,
+  clear_cache
+))]
+mod window;
 
+#[cfg(event)]
+pub(crate) use init::emit_event_js;
 use init::init;
+pub(crate) use init::set_init_data;
 
-#[cfg(assets)]
+#[cfg(any(assets, prefetch_assets))]
 mod asset;
+#[cfg(asset_protocol)]
+mod asset_protocol;
 #[cfg(open)]
 mod browser;
-#[cfg(any(open_dialog, save_dialog))]
+#[cfg(any(open_dialog, save_dialog, message_dialog, pick_scope_folder))]
 mod dialog;
 #[cfg(event)]
 mod event;
-#[cfg(http_request)]
+#[cfg(any(http_request, set_proxy))]
 mod http;
 #[cfg(notification)]
 mod notification;
+#[cfg(any(shell_open, file_association))]
+mod shell;
+#[cfg(store)]
+mod store;
 
+use crate::error::{ErrorCategory, ErrorPayload};
 use web_view::WebView;
 
+/// Handles a built-in command sent by the webview.
+///
+/// This runs on the webview thread, so the built-in commands are split in two groups:
+/// - the blocking commands (file system, HTTP, `execute`, assets, CLI matches, store, notifications,
+///   message dialogs and log path)
+///   run their work on the thread pool with `crate::execute_promise`
+///   and dispatch the result back to the webview, so they never freeze the UI;
+/// - the commands that only touch the webview (title, focus, theme, events...) and the file dialogs,
+///   which must be shown from the webview thread, run synchronously.
+///
+/// New built-in commands doing IO must use `crate::execute_promise`.
 #[allow(unused_variables)]
 pub(crate) fn handle<T: 'static>(webview: &mut WebView<'_, T>, arg: &str) -> crate::Result<()> {
   use cmd::Cmd::*;
@@ -30,11 +68,16 @@ pub(crate) fn handle<T: 'static>(webview: &mut WebView<'_, T>, arg: &str) -> cra
       match command {
         Init {} => {
           let event_init = init()?;
+          // the surface token lets the runner tell the splashscreen from the main content
           webview.eval(&format!(
             r#"{event_init}
-                window.external.invoke('{{"cmd":"__initialized"}}')
+                window.external.invoke(JSON.stringify({{
+                  cmd: '__initialized',
+                  surface: document.documentElement.getAttribute('{surface_attribute}')
+                }}))
               "#,
-            event_init = event_init
+            event_init = event_init,
+            surface_attribute = crate::app::SURFACE_TOKEN_ATTRIBUTE
           ))?;
         }
         ReadTextFile {
@@ -153,10 +196,205 @@ pub(crate) fn handle<T: 'static>(webview: &mut WebView<'_, T>, arg: &str) -> cra
         }
         SetTitle { title } => {
           #[cfg(set_title)]
-          webview.set_title(&title)?;
+          webview.set_title(&tauri_api::config::get()?.resolve_title(&title)?)?;
           #[cfg(not(set_title))]
           throw_whitelist_error(webview, "title");
         }
+        SetSpellcheck { enabled } => {
+          #[cfg(set_spellcheck)]
+          webview.eval(&init::spellcheck_js(enabled))?;
+          #[cfg(not(set_spellcheck))]
+          throw_whitelist_error(webview, "setSpellcheck");
+        }
+        IsFocused { callback, error } => {
+          #[cfg(focus)]
+          window::is_focused(webview, callback, error)?;
+          #[cfg(not(focus))]
+          whitelist_error(webview, error, "focus");
+        }
+        SetRequestHeaders { headers } => {
+          #[cfg(request_headers)]
+          window::set_request_headers(webview, headers)?;
+          #[cfg(not(request_headers))]
+          throw_whitelist_error(webview, "requestHeaders");
+        }
+        SetActivationPolicy { policy } => {
+          #[cfg(activation_policy)]
+          window::set_activation_policy(webview, policy)?;
+          #[cfg(not(activation_policy))]
+          throw_whitelist_error(webview, "activationPolicy");
+        }
+        SetCloseConfirmation { enabled } => {
+          #[cfg(close_confirmation)]
+          window::set_close_confirmation(webview, enabled)?;
+          #[cfg(not(close_confirmation))]
+          throw_whitelist_error(webview, "closeConfirmation");
+        }
+        ScaleFactor { callback, error } => {
+          #[cfg(scale_factor)]
+          window::scale_factor(webview, callback, error)?;
+          #[cfg(not(scale_factor))]
+          whitelist_error(webview, error, "scaleFactor");
+        }
+        GetTheme { callback, error } => {
+          #[cfg(theme)]
+          window::get_theme(webview, callback, error)?;
+          #[cfg(not(theme))]
+          whitelist_error(webview, error, "theme");
+        }
+        SetLoading {
+          visible,
+          message,
+          timeout,
+        } => {
+          #[cfg(set_loading)]
+          window::set_loading(webview, visible, message, timeout)?;
+          #[cfg(not(set_loading))]
+          throw_whitelist_error(webview, "setLoading");
+        }
+        SetBadge { count } => {
+          #[cfg(set_badge)]
+          window::set_badge(webview, count)?;
+          #[cfg(not(set_badge))]
+          throw_whitelist_error(webview, "setBadge");
+        }
+        SetVisibleOnAllWorkspaces { value } => {
+          #[cfg(visible_on_all_workspaces)]
+          window::set_visible_on_all_workspaces(webview, value)?;
+          #[cfg(not(visible_on_all_workspaces))]
+          throw_whitelist_error(webview, "visibleOnAllWorkspaces");
+        }
+        Print {
+          silent,
+          callback,
+          error,
+        } => {
+          #[cfg(print)]
+          window::print(webview, silent, callback, error)?;
+          #[cfg(not(print))]
+          whitelist_error(webview, error, "print");
+        }
+        GetCookies {
+          url,
+          callback,
+          error,
+        } => {
+          #[cfg(cookies)]
+          window::get_cookies(webview, url, callback, error)?;
+          #[cfg(not(cookies))]
+          whitelist_error(webview, error, "cookies");
+        }
+        SetCookie {
+          cookie,
+          url,
+          callback,
+          error,
+        } => {
+          #[cfg(cookies)]
+          window::set_cookie(webview, cookie, url, callback, error)?;
+          #[cfg(not(cookies))]
+          whitelist_error(webview, error, "cookies");
+        }
+        DeleteCookie {
+          name,
+          path,
+          url,
+          callback,
+          error,
+        } => {
+          #[cfg(cookies)]
+          window::delete_cookie(webview, name, path, url, callback, error)?;
+          #[cfg(not(cookies))]
+          whitelist_error(webview, error, "cookies");
+        }
+        GetMediaPermissions { callback, error } => {
+          #[cfg(media_permissions)]
+          window::get_media_permissions(webview, callback, error)?;
+          #[cfg(not(media_permissions))]
+          whitelist_error(webview, error, "mediaPermissions");
+        }
+        RequestMediaPermissions {
+          camera,
+          microphone,
+          callback,
+          error,
+        } => {
+          #[cfg(media_permissions)]
+          window::request_media_permissions(webview, camera, microphone, callback, error)?;
+          #[cfg(not(media_permissions))]
+          whitelist_error(webview, error, "mediaPermissions");
+        }
+        ClearCache {
+          kinds,
+          callback,
+          error,
+        } => {
+          #[cfg(clear_cache)]
+          window::clear_cache(webview, kinds, callback, error)?;
+          #[cfg(not(clear_cache))]
+          whitelist_error(webview, error, "clearCache");
+        }
+        HashFile {
+          path,
+          algo,
+          options,
+          callback,
+          error,
+        } => {
+          #[cfg(hash_file)]
+          file_system::hash_file(webview, path, algo, options, callback, error);
+          #[cfg(not(hash_file))]
+          whitelist_error(webview, error, "hashFile");
+        }
+        Watch {
+          paths,
+          recursive,
+          callback,
+          error,
+        } => {
+          #[cfg(watch)]
+          file_system::watch(webview, paths, recursive, callback, error);
+          #[cfg(not(watch))]
+          whitelist_error(webview, error, "watch");
+        }
+        Unwatch {
+          id,
+          callback,
+          error,
+        } => {
+          #[cfg(watch)]
+          file_system::unwatch(webview, id, callback, error);
+          #[cfg(not(watch))]
+          whitelist_error(webview, error, "watch");
+        }
+        DirSize {
+          id,
+          base,
+          path,
+          callback,
+          error,
+        } => {
+          #[cfg(dir_size)]
+          file_system::dir_size(webview, id, base, path, callback, error);
+          #[cfg(not(dir_size))]
+          whitelist_error(webview, error, "dirSize");
+        }
+        CancelDirSize { id } => {
+          #[cfg(dir_size)]
+          file_system::cancel_dir_size(id);
+          #[cfg(not(dir_size))]
+          throw_whitelist_error(webview, "dirSize");
+        }
+        DiskSpace {
+          path,
+          callback,
+          error,
+        } => {
+          #[cfg(disk_space)]
+          file_system::disk_space(webview, path, callback, error);
+          #[cfg(not(disk_space))]
+          whitelist_error(webview, error, "diskSpace");
+        }
         Execute {
           command,
           args,
@@ -174,6 +412,52 @@ pub(crate) fn handle<T: 'static>(webview: &mut WebView<'_, T>, arg: &str) -> cra
           #[cfg(not(open))]
           throw_whitelist_error(webview, "open");
         }
+        OpenExternalLink { uri } => {
+          #[cfg(open)]
+          window::open_external_link(uri)?;
+          #[cfg(not(open))]
+          throw_whitelist_error(webview, "open");
+        }
+        NavigationBlocked { url } => {
+          eprintln!(
+            "blocked the navigation to {}, which is not in tauri.security.navigationAllowlist",
+            url
+          );
+        }
+        RegisterFileAssociation {
+          ext,
+          description,
+          callback,
+          error,
+        } => {
+          #[cfg(file_association)]
+          shell::register_file_association(webview, ext, description, callback, error);
+          #[cfg(not(file_association))]
+          whitelist_error(webview, error, "fileAssociation");
+        }
+        ListChildren { callback, error } => {
+          #[cfg(child_processes)]
+          crate::execute_promise(
+            webview,
+            || Ok(tauri_api::command::children()),
+            callback,
+            error,
+          );
+          #[cfg(not(child_processes))]
+          whitelist_error(webview, error, "childProcesses");
+        }
+        KillChildren { callback, error } => {
+          #[cfg(child_processes)]
+          crate::execute_promise(webview, tauri_api::command::kill_children, callback, error);
+          #[cfg(not(child_processes))]
+          whitelist_error(webview, error, "childProcesses");
+        }
+        ShellOpen { path } => {
+          #[cfg(shell_open)]
+          shell::open(path)?;
+          #[cfg(not(shell_open))]
+          throw_whitelist_error(webview, "shellOpen");
+        }
         ValidateSalt {
           salt,
           callback,
@@ -210,6 +494,16 @@ pub(crate) fn handle<T: 'static>(webview: &mut WebView<'_, T>, arg: &str) -> cra
           #[cfg(not(open_dialog))]
           whitelist_error(webview, error, "title");
         }
+        PickScopeFolder {
+          persist,
+          callback,
+          error,
+        } => {
+          #[cfg(pick_scope_folder)]
+          dialog::pick_scope_folder(webview, persist, callback, error)?;
+          #[cfg(not(pick_scope_folder))]
+          whitelist_error(webview, error, "pickScopeFolder");
+        }
         SaveDialog {
           options,
           callback,
@@ -220,6 +514,26 @@ pub(crate) fn handle<T: 'static>(webview: &mut WebView<'_, T>, arg: &str) -> cra
           #[cfg(not(save_dialog))]
           throw_whitelist_error(webview, "saveDialog");
         }
+        MessageDialog {
+          options,
+          callback,
+          error,
+        } => {
+          #[cfg(message_dialog)]
+          dialog::message(webview, options, callback, error);
+          #[cfg(not(message_dialog))]
+          whitelist_error(webview, error, "messageDialog");
+        }
+        ConfirmDialog {
+          options,
+          callback,
+          error,
+        } => {
+          #[cfg(message_dialog)]
+          dialog::confirm(webview, options, callback, error);
+          #[cfg(not(message_dialog))]
+          whitelist_error(webview, error, "messageDialog");
+        }
         HttpRequest {
           options,
           callback,
@@ -230,6 +544,27 @@ pub(crate) fn handle<T: 'static>(webview: &mut WebView<'_, T>, arg: &str) -> cra
           #[cfg(not(http_request))]
           whitelist_error(webview, error, "httpRequest");
         }
+        SetProxy {
+          url,
+          bypass,
+          callback,
+          error,
+        } => {
+          #[cfg(set_proxy)]
+          http::set_proxy(webview, url, bypass, callback, error);
+          #[cfg(not(set_proxy))]
+          whitelist_error(webview, error, "setProxy");
+        }
+        PrefetchAssets {
+          paths,
+          callback,
+          error,
+        } => {
+          #[cfg(prefetch_assets)]
+          asset::prefetch(webview, paths, callback, error);
+          #[cfg(not(prefetch_assets))]
+          whitelist_error(webview, error, "prefetchAssets");
+        }
         #[cfg(assets)]
         LoadAsset {
           asset,
@@ -239,6 +574,26 @@ pub(crate) fn handle<T: 'static>(webview: &mut WebView<'_, T>, arg: &str) -> cra
         } => {
           asset::load(webview, asset, asset_type, callback, error);
         }
+        RegisterAssetProtocol {
+          scheme,
+          callback,
+          error,
+        } => {
+          #[cfg(asset_protocol)]
+          asset_protocol::register(webview, scheme, callback, error)?;
+          #[cfg(not(asset_protocol))]
+          whitelist_error(webview, error, "assetProtocol");
+        }
+        ResolveAssetUrl {
+          path,
+          callback,
+          error,
+        } => {
+          #[cfg(asset_protocol)]
+          asset_protocol::resolve_url(webview, path, callback, error);
+          #[cfg(not(asset_protocol))]
+          whitelist_error(webview, error, "assetProtocol");
+        }
         CliMatches { callback, error } => {
           #[cfg(cli)]
           crate::execute_promise(
@@ -253,6 +608,43 @@ pub(crate) fn handle<T: 'static>(webview: &mut WebView<'_, T>, arg: &str) -> cra
           #[cfg(not(cli))]
           whitelist_error(webview, error, "cli");
         }
+        StoreGet {
+          key,
+          callback,
+          error,
+        } => {
+          #[cfg(store)]
+          store::get(webview, key, callback, error);
+          #[cfg(not(store))]
+          whitelist_error(webview, error, "store");
+        }
+        StoreSet {
+          key,
+          value,
+          callback,
+          error,
+        } => {
+          #[cfg(store)]
+          store::set(webview, key, value, callback, error);
+          #[cfg(not(store))]
+          whitelist_error(webview, error, "store");
+        }
+        StoreDelete {
+          key,
+          callback,
+          error,
+        } => {
+          #[cfg(store)]
+          store::delete(webview, key, callback, error);
+          #[cfg(not(store))]
+          whitelist_error(webview, error, "store");
+        }
+        StoreSave { callback, error } => {
+          #[cfg(store)]
+          store::save(webview, callback, error);
+          #[cfg(not(store))]
+          whitelist_error(webview, error, "store");
+        }
         Notification {
           options,
           callback,
@@ -275,6 +667,154 @@ pub(crate) fn handle<T: 'static>(webview: &mut WebView<'_, T>, arg: &str) -> cra
           #[cfg(not(notification))]
           whitelist_error(webview, error, "notification");
         }
+        Log {
+          level,
+          message,
+          source,
+        } => {
+          #[cfg(logger)]
+          match source {
+            Some(source) => {
+              log::log!(target: "webview", log::Level::from(level), "{} ({})", message, source)
+            }
+            None => log::log!(target: "webview", log::Level::from(level), "{}", message),
+          }
+          #[cfg(not(logger))]
+          throw_whitelist_error(webview, "logger");
+        }
+        GetLogPath { callback, error } => {
+          #[cfg(logger)]
+          crate::execute_promise(webview, crate::logger::log_path, callback, error);
+          #[cfg(not(logger))]
+          whitelist_error(webview, error, "logger");
+        }
+        IsInstalled { callback, error } => {
+          #[cfg(is_installed)]
+          crate::execute_promise(webview, tauri_api::install::location, callback, error);
+          #[cfg(not(is_installed))]
+          whitelist_error(webview, error, "isInstalled");
+        }
+        GetPlatform { callback, error } => {
+          #[cfg(get_platform)]
+          crate::execute_promise(
+            webview,
+            || {
+              Ok(serde_json::json!({
+                "os": std::env::consts::OS,
+                "arch": std::env::consts::ARCH,
+                "version": tauri_api::platform::os_version(),
+                "family": std::env::consts::FAMILY,
+              }))
+            },
+            callback,
+            error,
+          );
+          #[cfg(not(get_platform))]
+          whitelist_error(webview, error, "getPlatform");
+        }
+        GetPowerState { callback, error } => {
+          #[cfg(power_state)]
+          crate::execute_promise(webview, tauri_api::power::state, callback, error);
+          #[cfg(not(power_state))]
+          whitelist_error(webview, error, "powerState");
+        }
+        GetConfig { callback, error } => {
+          #[cfg(get_config)]
+          crate::execute_promise(webview, tauri_api::config::to_json, callback, error);
+          #[cfg(not(get_config))]
+          whitelist_error(webview, error, "getConfig");
+        }
+        GetLocale { callback, error } => {
+          #[cfg(locale)]
+          crate::execute_promise(webview, tauri_api::locale::locale, callback, error);
+          #[cfg(not(locale))]
+          whitelist_error(webview, error, "locale");
+        }
+        GetPreferredLanguages { callback, error } => {
+          #[cfg(locale)]
+          crate::execute_promise(
+            webview,
+            tauri_api::locale::preferred_languages,
+            callback,
+            error,
+          );
+          #[cfg(not(locale))]
+          whitelist_error(webview, error, "locale");
+        }
+        GetExePath { callback, error } => {
+          #[cfg(exe_path)]
+          crate::execute_promise(
+            webview,
+            || std::env::current_exe().map_err(Into::into),
+            callback,
+            error,
+          );
+          #[cfg(not(exe_path))]
+          whitelist_error(webview, error, "exePath");
+        }
+        GetCwd { callback, error } => {
+          #[cfg(cwd)]
+          crate::execute_promise(
+            webview,
+            || std::env::current_dir().map_err(Into::into),
+            callback,
+            error,
+          );
+          #[cfg(not(cwd))]
+          whitelist_error(webview, error, "cwd");
+        }
+        InstallUpdate { callback, error } => {
+          #[cfg(install_update)]
+          {
+            let handle = webview.handle();
+            crate::execute_promise(
+              webview,
+              move || crate::updater::install(&handle),
+              callback,
+              error,
+            );
+          }
+          #[cfg(not(install_update))]
+          whitelist_error(webview, error, "installUpdate");
+        }
+        GetStartupMetrics { callback, error } => {
+          #[cfg(startup_metrics)]
+          crate::execute_promise(webview, || Ok(crate::startup::metrics()), callback, error);
+          #[cfg(not(startup_metrics))]
+          whitelist_error(webview, error, "startupMetrics");
+        }
+        GetNetworkStatus { callback, error } => {
+          #[cfg(network_status)]
+          crate::execute_promise(webview, tauri_api::network::status, callback, error);
+          #[cfg(not(network_status))]
+          whitelist_error(webview, error, "networkStatus");
+        }
+        GetIdleTime { callback, error } => {
+          #[cfg(idle_time)]
+          crate::execute_promise(
+            webview,
+            || tauri_api::idle::idle_time().map(|idle| idle.as_secs()),
+            callback,
+            error,
+          );
+          #[cfg(not(idle_time))]
+          whitelist_error(webview, error, "idleTime");
+        }
+        SetPreventSleep {
+          enabled,
+          callback,
+          error,
+        } => {
+          #[cfg(prevent_sleep)]
+          crate::execute_promise(
+            webview,
+            move || tauri_api::idle::prevent_sleep(enabled),
+            callback,
+            error,
+          );
+          #[cfg(not(prevent_sleep))]
+          whitelist_error(webview, error, "preventSleep");
+        }
       }
       Ok(())
     }
@@ -287,9 +827,12 @@ fn whitelist_error<T: 'static>(
   error_fn: String,
   whitelist_key: &str,
 ) {
-  let reject_code = tauri_api::rpc::format_callback(
+  let reject_code = tauri_api::rpc::format_callback_error(
     error_fn,
-    format!(r#""'{}' not whitelisted""#, whitelist_key),
+    ErrorPayload::new(
+      ErrorCategory::Command,
+      format!("'{}' not whitelisted", whitelist_key),
+    ),
   );
   webview
     .eval(&reject_code)
@@ -326,6 +869,29 @@ mod test {
     }
   }
 
+  #[test]
+  // check that the init data is exposed by the init script
+  fn check_init_data() {
+    super::set_init_data(r#"{"user":"tauri"}"#.to_string());
+    let init = super::init().expect("failed to get the init script");
+    assert!(init.starts_with(r#"window.__TAURI_INIT__ = Object.freeze({"user":"tauri"});"#));
+  }
+
+  #[test]
+  // check that the JS strings of the console forwarding don't span lines, which is a syntax error
+  fn check_console_forwarding_js() {
+    let js = super::init::CONSOLE_FORWARDING_JS;
+    assert!(js.contains(r"split('\n')"));
+    for line in js.lines() {
+      assert_eq!(
+        line.matches('\'').count() % 2,
+        0,
+        "unterminated string in {}",
+        line
+      );
+    }
+  }
+
   // check the listen_fn for various usecases.
   proptest! {
     #[cfg(event)]
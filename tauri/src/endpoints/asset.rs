@@ -1,6 +1,8 @@
+#[cfg(assets)]
 use std::path::PathBuf;
 use web_view::WebView;
 
+#[cfg(assets)]
 pub fn load<T: 'static>(
   webview: &mut WebView<'_, T>,
   asset: String,
@@ -77,3 +79,36 @@ pub fn load<T: 'static>(
     error,
   );
 }
+
+/// Loads the given asset paths in the embedded server memory, so the next requests for them
+/// don't read the disk. Rejects with the paths that couldn't be loaded.
+///
+/// Without the embedded server the assets aren't served by the app, so there's nothing to prefetch.
+#[cfg(prefetch_assets)]
+pub fn prefetch<T: 'static>(
+  webview: &mut WebView<'_, T>,
+  paths: Vec<String>,
+  callback: String,
+  error: String,
+) {
+  crate::execute_promise(
+    webview,
+    move || {
+      #[cfg(embedded_server)]
+      {
+        let failed = crate::server::prefetch_assets(&paths);
+        if !failed.is_empty() {
+          return Err(anyhow::anyhow!(
+            "failed to prefetch the assets {}",
+            failed.join(", ")
+          ));
+        }
+      }
+      #[cfg(not(embedded_server))]
+      let _ = paths;
+      Ok(())
+    },
+    callback,
+    error,
+  );
+}
@@ -0,0 +1,212 @@
+use web_view::WebView;
+
+/// The schemes handled by the webview or the OS, which can't be registered as asset protocols.
+const BUILTIN_SCHEMES: &[&str] = &[
+  "about",
+  "blob",
+  "chrome",
+  "data",
+  "edge",
+  "file",
+  "filesystem",
+  "ftp",
+  "http",
+  "https",
+  "javascript",
+  "mailto",
+  "ms-appx",
+  "ms-appx-web",
+  "tauri",
+  "tel",
+  "view-source",
+  "webkit",
+  "ws",
+  "wss",
+];
+
+/// Validates the given asset protocol scheme, returning it in lowercase.
+///
+/// The scheme must follow the RFC 3986 syntax (a letter followed by letters, digits, `+`, `-` or `.`)
+/// and must not be one of the `BUILTIN_SCHEMES`.
+fn validate_scheme(scheme: &str) -> crate::Result<String> {
+  let scheme = scheme.to_lowercase();
+  let valid = scheme
+    .chars()
+    .next()
+    .map_or(false, |c| c.is_ascii_alphabetic())
+    && scheme
+      .chars()
+      .all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-' || c == '.');
+  if !valid {
+    return Err(
+      crate::Error::Command(format!("invalid asset protocol scheme '{}'", scheme)).into(),
+    );
+  }
+  if BUILTIN_SCHEMES.contains(&scheme.as_str()) {
+    return Err(
+      crate::Error::Command(format!(
+        "'{}' is a built-in scheme and can't be registered as an asset protocol",
+        scheme
+      ))
+      .into(),
+    );
+  }
+  Ok(scheme)
+}
+
+/// Registers the given scheme as an asset protocol, so the `src` and `href` attributes
+/// starting with `<scheme>://` are resolved to the app assets.
+///
+/// The webview backends can't register custom URL schemes, so the attributes are rewritten
+/// by a `MutationObserver` with the URLs resolved by `resolve_url`.
+pub fn register<T: 'static>(
+  webview: &mut WebView<'_, T>,
+  scheme: String,
+  callback: String,
+  error: String,
+) -> crate::Result<()> {
+  let result = validate_scheme(&scheme).and_then(|scheme| {
+    webview.eval(&format!(
+      r#"
+        (function (prefix) {{
+          var registered = window.__TAURI_ASSET_PROTOCOLS__ = window.__TAURI_ASSET_PROTOCOLS__ || []
+          if (registered.indexOf(prefix) !== -1) {{
+            return
+          }}
+          registered.push(prefix)
+          function resolve(element, attribute) {{
+            var value = element.getAttribute(attribute)
+            if (value && value.toLowerCase().indexOf(prefix) === 0) {{
+              window.__TAURI__.promisified({{
+                cmd: 'resolveAssetUrl',
+                path: value.slice(prefix.length)
+              }}).then(function (url) {{
+                element.setAttribute(attribute, url)
+              }}, function (e) {{
+                console.error('failed to resolve ' + value + ': ' + e)
+              }})
+            }}
+          }}
+          function resolveTree(root) {{
+            if (root.nodeType !== 1) {{
+              return
+            }}
+            resolve(root, 'src')
+            resolve(root, 'href')
+            var elements = root.querySelectorAll('[src], [href]')
+            for (var i = 0; i < elements.length; i++) {{
+              resolve(elements[i], 'src')
+              resolve(elements[i], 'href')
+            }}
+          }}
+          new MutationObserver(function (mutations) {{
+            mutations.forEach(function (mutation) {{
+              if (mutation.type === 'attributes') {{
+                resolve(mutation.target, mutation.attributeName)
+              }} else {{
+                mutation.addedNodes.forEach(resolveTree)
+              }}
+            }})
+          }}).observe(document.documentElement, {{
+            childList: true,
+            subtree: true,
+            attributes: true,
+            attributeFilter: ['src', 'href']
+          }})
+          resolveTree(document.documentElement)
+        }})({prefix})
+      "#,
+      prefix = serde_json::to_string(&format!("{}://", scheme))?
+    ))?;
+    Ok(())
+  });
+  crate::execute_promise_sync(webview, move || result, callback, error)
+}
+
+/// Resolves the URL that loads the given asset path.
+///
+/// The bundled assets are resolved to a data URL, and on the dev server they're resolved
+/// to the path relative to the page origin.
+pub fn resolve_url<T: 'static>(
+  webview: &mut WebView<'_, T>,
+  path: String,
+  callback: String,
+  error: String,
+) {
+  crate::execute_promise(
+    webview,
+    move || {
+      let path = path.trim_start_matches('/').to_string();
+      if path.split('/').any(|component| component == "..") {
+        return Err(crate::Error::Command(format!("invalid asset path '{}'", path)).into());
+      }
+      asset_url(&path)
+    },
+    callback,
+    error,
+  );
+}
+
+#[cfg(assets)]
+fn asset_url(path: &str) -> crate::Result<String> {
+  let asset = crate::assets::ASSETS
+    .get(&format!(
+      "{}/{}",
+      option_env!("TAURI_DIST_DIR")
+        .expect("tauri apps should be built with the TAURI_DIST_DIR environment variable"),
+      path
+    ))
+    .map_err(|_| anyhow::anyhow!("Asset '{}' not found", path))?;
+  Ok(format!(
+    "data:{};base64,{}",
+    mime_type(path),
+    base64::encode(&asset.into_owned())
+  ))
+}
+
+#[cfg(not(assets))]
+fn asset_url(path: &str) -> crate::Result<String> {
+  Ok(format!("/{}", path))
+}
+
+/// Gets the MIME type of the data URL of the given asset path.
+#[cfg(assets)]
+fn mime_type(path: &str) -> &'static str {
+  let extension = path.rsplit('.').next().unwrap_or("").to_lowercase();
+  match extension.as_str() {
+    "css" => "text/css",
+    "gif" => "image/gif",
+    "html" => "text/html",
+    "ico" => "image/x-icon",
+    "jpg" | "jpeg" => "image/jpeg",
+    "js" => "text/javascript",
+    "json" => "application/json",
+    "mp3" => "audio/mpeg",
+    "mp4" => "video/mp4",
+    "png" => "image/png",
+    "svg" => "image/svg+xml",
+    "wasm" => "application/wasm",
+    "webp" => "image/webp",
+    "woff" => "font/woff",
+    "woff2" => "font/woff2",
+    _ => "application/octet-stream",
+  }
+}
+
+#[cfg(test)]
+mod test {
+  #[test]
+  // check that the invalid and the built-in schemes are rejected
+  fn check_validate_scheme() {
+    assert_eq!(super::validate_scheme("Asset").unwrap(), "asset");
+    assert_eq!(
+      super::validate_scheme("app-assets.v2").unwrap(),
+      "app-assets.v2"
+    );
+    assert!(super::validate_scheme("").is_err());
+    assert!(super::validate_scheme("1asset").is_err());
+    assert!(super::validate_scheme("asset://").is_err());
+    assert!(super::validate_scheme("HTTPS").is_err());
+    assert!(super::validate_scheme("file").is_err());
+  }
+}
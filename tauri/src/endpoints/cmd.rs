@@ -1,11 +1,14 @@
+use std::collections::HashMap;
 use std::path::PathBuf;
 
 use crate::api::path::BaseDirectory;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
 use tauri_api::http::HttpRequestOptions;
 
 /// The options for the directory functions on the file system API.
 #[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct DirOperationOptions {
   /// Whether the API should recursively perform the operation on the directory.
   #[serde(default)]
@@ -13,6 +16,9 @@ pub struct DirOperationOptions {
   /// The base directory of the operation.
   /// The directory path of the BaseDirectory will be the prefix of the defined directory path.
   pub dir: Option<BaseDirectory>,
+  /// How many subdirectory levels the recursive `readDir` reads.
+  #[serde(default)]
+  pub max_depth: Option<usize>,
 }
 
 /// The options for the file functions on the file system API.
@@ -49,6 +55,37 @@ pub struct SaveDialogOptions {
   pub default_path: Option<PathBuf>,
 }
 
+/// The kind of a message dialog.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MessageDialogKind {
+  /// The info dialog.
+  Info,
+  /// The warning dialog.
+  Warning,
+  /// The error dialog.
+  Error,
+}
+
+impl Default for MessageDialogKind {
+  fn default() -> Self {
+    Self::Info
+  }
+}
+
+/// The options for the message and confirm dialog APIs.
+#[derive(Deserialize)]
+pub struct MessageDialogOptions {
+  /// The dialog title.
+  #[serde(default)]
+  pub title: String,
+  /// The dialog message.
+  pub message: String,
+  /// The dialog kind.
+  #[serde(default)]
+  pub kind: MessageDialogKind,
+}
+
 /// The options for the notification API.
 #[derive(Deserialize)]
 pub struct NotificationOptions {
@@ -60,6 +97,89 @@ pub struct NotificationOptions {
   pub icon: Option<String>,
 }
 
+/// The macOS app activation policies.
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum ActivationPolicy {
+  /// The app has a dock icon and can show the menu bar.
+  Regular = 0,
+  /// The app has no dock icon, e.g. a menu bar utility, but its windows can be activated.
+  Accessory = 1,
+  /// The app has no dock icon and can't be activated.
+  Prohibited = 2,
+}
+
+/// The app directories measured by the `dirSize` API.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AppDirectory {
+  /// The app data directory, i.e. the `App` base directory.
+  AppData,
+  /// The app cache directory.
+  AppCache,
+}
+
+/// The webview storage kinds.
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StorageKind {
+  /// The Cache Storage.
+  Cache,
+  /// The cookies of the current origin.
+  Cookies,
+  /// The local storage.
+  LocalStorage,
+  /// The session storage.
+  SessionStorage,
+}
+
+/// A cookie to set on the webview.
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Cookie {
+  /// The cookie name.
+  pub name: String,
+  /// The cookie value.
+  pub value: String,
+  /// The path the cookie is sent for.
+  pub path: Option<String>,
+  /// The cookie lifetime in seconds. If it's not set, the cookie lasts for the session.
+  pub max_age: Option<i64>,
+  /// Whether the cookie is only sent over HTTPS.
+  #[serde(default)]
+  pub secure: bool,
+  /// The cookie SameSite policy: `strict`, `lax` or `none`.
+  pub same_site: Option<String>,
+}
+
+/// The level of a log line.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LogLevel {
+  /// The trace level.
+  Trace,
+  /// The debug level.
+  Debug,
+  /// The info level.
+  Info,
+  /// The warn level.
+  Warn,
+  /// The error level.
+  Error,
+}
+
+impl From<LogLevel> for log::Level {
+  fn from(level: LogLevel) -> Self {
+    match level {
+      LogLevel::Trace => log::Level::Trace,
+      LogLevel::Debug => log::Level::Debug,
+      LogLevel::Info => log::Level::Info,
+      LogLevel::Warn => log::Level::Warn,
+      LogLevel::Error => log::Level::Error,
+    }
+  }
+}
+
 /// The API descriptor.
 #[derive(Deserialize)]
 #[serde(tag = "cmd", rename_all = "camelCase")]
@@ -143,6 +263,113 @@ pub enum Cmd {
   },
   /// The set webview title API.
   SetTitle { title: String },
+  /// The set spellcheck API.
+  SetSpellcheck { enabled: bool },
+  /// The window focus check API.
+  IsFocused { callback: String, error: String },
+  /// The get window theme API.
+  GetTheme { callback: String, error: String },
+  /// The close confirmation API.
+  SetCloseConfirmation { enabled: bool },
+  /// The request headers API.
+  SetRequestHeaders { headers: HashMap<String, String> },
+  /// The macOS activation policy API.
+  SetActivationPolicy { policy: ActivationPolicy },
+  /// The window scale factor API.
+  ScaleFactor { callback: String, error: String },
+  /// The loading overlay API.
+  SetLoading {
+    visible: bool,
+    message: Option<String>,
+    timeout: Option<u64>,
+  },
+  /// The set badge count API.
+  SetBadge { count: Option<u64> },
+  /// The set visible on all workspaces API.
+  SetVisibleOnAllWorkspaces { value: bool },
+  /// The print API.
+  Print {
+    #[serde(default)]
+    silent: bool,
+    callback: String,
+    error: String,
+  },
+  /// The clear webview storage API.
+  ClearCache {
+    kinds: Vec<StorageKind>,
+    callback: String,
+    error: String,
+  },
+  /// The get cookies API.
+  GetCookies {
+    url: Option<String>,
+    callback: String,
+    error: String,
+  },
+  /// The set cookie API.
+  SetCookie {
+    cookie: Cookie,
+    url: Option<String>,
+    callback: String,
+    error: String,
+  },
+  /// The delete cookie API.
+  DeleteCookie {
+    name: String,
+    path: Option<String>,
+    url: Option<String>,
+    callback: String,
+    error: String,
+  },
+  /// The media permissions state API.
+  GetMediaPermissions { callback: String, error: String },
+  /// The media permissions request API.
+  RequestMediaPermissions {
+    #[serde(default)]
+    camera: bool,
+    #[serde(default)]
+    microphone: bool,
+    callback: String,
+    error: String,
+  },
+  /// The file hash API.
+  HashFile {
+    path: PathBuf,
+    algo: String,
+    options: Option<FileOperationOptions>,
+    callback: String,
+    error: String,
+  },
+  /// The file system watch API.
+  Watch {
+    paths: Vec<PathBuf>,
+    #[serde(default)]
+    recursive: bool,
+    callback: String,
+    error: String,
+  },
+  /// The file system unwatch API.
+  Unwatch {
+    id: u32,
+    callback: String,
+    error: String,
+  },
+  /// The directory size API.
+  DirSize {
+    id: u32,
+    base: AppDirectory,
+    path: Option<PathBuf>,
+    callback: String,
+    error: String,
+  },
+  /// Cancels a directory size computation.
+  CancelDirSize { id: u32 },
+  /// The disk space API.
+  DiskSpace {
+    path: PathBuf,
+    callback: String,
+    error: String,
+  },
   /// The execute script API.
   Execute {
     command: String,
@@ -152,6 +379,15 @@ pub enum Cmd {
   },
   /// The open URL in browser API
   Open { uri: String },
+  /// The open path with the default application API.
+  ShellOpen { path: PathBuf },
+  /// The register file association API.
+  RegisterFileAssociation {
+    ext: String,
+    description: Option<String>,
+    callback: String,
+    error: String,
+  },
   ValidateSalt {
     salt: String,
     callback: String,
@@ -174,18 +410,51 @@ pub enum Cmd {
     callback: String,
     error: String,
   },
+  /// The pick scope folder API.
+  PickScopeFolder {
+    #[serde(default)]
+    persist: bool,
+    callback: String,
+    error: String,
+  },
   /// The save dialog API.
   SaveDialog {
     options: SaveDialogOptions,
     callback: String,
     error: String,
   },
+  /// The message dialog API.
+  MessageDialog {
+    options: MessageDialogOptions,
+    callback: String,
+    error: String,
+  },
+  /// The confirm dialog API.
+  ConfirmDialog {
+    options: MessageDialogOptions,
+    callback: String,
+    error: String,
+  },
   /// The HTTP request API.
   HttpRequest {
     options: Box<HttpRequestOptions>,
     callback: String,
     error: String,
   },
+  /// The HTTP proxy API.
+  SetProxy {
+    url: Option<String>,
+    #[serde(default)]
+    bypass: Vec<String>,
+    callback: String,
+    error: String,
+  },
+  /// The embedded server assets prefetch API.
+  PrefetchAssets {
+    paths: Vec<String>,
+    callback: String,
+    error: String,
+  },
   /// The load asset into webview API.
   #[serde(rename_all = "camelCase")]
   #[cfg(assets)]
@@ -195,8 +464,41 @@ pub enum Cmd {
     callback: String,
     error: String,
   },
+  /// The register asset protocol API.
+  RegisterAssetProtocol {
+    scheme: String,
+    callback: String,
+    error: String,
+  },
+  /// The asset URL resolution, sent by the registered asset protocols.
+  ResolveAssetUrl {
+    path: String,
+    callback: String,
+    error: String,
+  },
   /// The get CLI matches API.
   CliMatches { callback: String, error: String },
+  /// The app store get API.
+  StoreGet {
+    key: String,
+    callback: String,
+    error: String,
+  },
+  /// The app store set API.
+  StoreSet {
+    key: String,
+    value: JsonValue,
+    callback: String,
+    error: String,
+  },
+  /// The app store delete API.
+  StoreDelete {
+    key: String,
+    callback: String,
+    error: String,
+  },
+  /// The app store save API.
+  StoreSave { callback: String, error: String },
   /// The show notification API.
   Notification {
     options: NotificationOptions,
@@ -207,4 +509,52 @@ pub enum Cmd {
   RequestNotificationPermission { callback: String, error: String },
   /// The notification permission check API.
   IsNotificationPermissionGranted { callback: String, error: String },
+  /// The open external link API, used by the `tauri.window.externalLinks` policy.
+  OpenExternalLink { uri: String },
+  /// The blocked navigation notice, sent by the `tauri.security.navigationAllowlist` guard.
+  NavigationBlocked { url: String },
+  /// The log API.
+  Log {
+    level: LogLevel,
+    message: String,
+    /// The source location of the forwarded console messages.
+    #[serde(default)]
+    source: Option<String>,
+  },
+  /// The log file path API.
+  GetLogPath { callback: String, error: String },
+  /// The list child processes API.
+  ListChildren { callback: String, error: String },
+  /// The kill child processes API.
+  KillChildren { callback: String, error: String },
+  /// The install location API.
+  IsInstalled { callback: String, error: String },
+  /// The platform information API.
+  GetPlatform { callback: String, error: String },
+  /// The battery and power source state API.
+  GetPowerState { callback: String, error: String },
+  /// The active config API.
+  GetConfig { callback: String, error: String },
+  /// The OS locale API.
+  GetLocale { callback: String, error: String },
+  /// The preferred languages API.
+  GetPreferredLanguages { callback: String, error: String },
+  /// The executable path API.
+  GetExePath { callback: String, error: String },
+  /// The working directory API.
+  GetCwd { callback: String, error: String },
+  /// The update install API.
+  InstallUpdate { callback: String, error: String },
+  /// The startup timing metrics API.
+  GetStartupMetrics { callback: String, error: String },
+  /// The network status API.
+  GetNetworkStatus { callback: String, error: String },
+  /// The user idle time API.
+  GetIdleTime { callback: String, error: String },
+  /// The system sleep prevention API.
+  SetPreventSleep {
+    enabled: bool,
+    callback: String,
+    error: String,
+  },
 }
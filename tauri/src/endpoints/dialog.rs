@@ -1,5 +1,8 @@
-use super::cmd::{OpenDialogOptions, SaveDialogOptions};
-use crate::api::dialog::{pick_folder, save_file, select, select_multiple, Response};
+use super::cmd::{MessageDialogKind, MessageDialogOptions, OpenDialogOptions, SaveDialogOptions};
+use crate::api::dialog::{
+  confirm as confirm_dialog, message as message_dialog, pick_folder, save_file, select,
+  select_multiple, DialogStyle, Response,
+};
 use serde_json::Value as JsonValue;
 use web_view::WebView;
 
@@ -12,6 +15,16 @@ fn map_response(response: Response) -> JsonValue {
   }
 }
 
+/// maps a message dialog kind to its style
+#[cfg(message_dialog)]
+fn map_kind(kind: MessageDialogKind) -> DialogStyle {
+  match kind {
+    MessageDialogKind::Info => DialogStyle::Info,
+    MessageDialogKind::Warning => DialogStyle::Warning,
+    MessageDialogKind::Error => DialogStyle::Error,
+  }
+}
+
 /// Shows an open dialog.
 #[cfg(open_dialog)]
 pub fn open<T: 'static>(
@@ -54,3 +67,77 @@ pub fn save<T: 'static>(
   )?;
   Ok(())
 }
+
+/// Shows a message dialog.
+///
+/// It's shown from the thread pool, so the webview thread keeps running while the dialog is open.
+#[cfg(message_dialog)]
+pub fn message<T: 'static>(
+  webview: &mut WebView<'_, T>,
+  options: MessageDialogOptions,
+  callback: String,
+  error: String,
+) {
+  crate::execute_promise(
+    webview,
+    move || message_dialog(options.message, options.title, map_kind(options.kind)),
+    callback,
+    error,
+  );
+}
+
+/// Shows a confirm dialog from the thread pool, resolving whether the user confirmed it.
+#[cfg(message_dialog)]
+pub fn confirm<T: 'static>(
+  webview: &mut WebView<'_, T>,
+  options: MessageDialogOptions,
+  callback: String,
+  error: String,
+) {
+  crate::execute_promise(
+    webview,
+    move || confirm_dialog(options.message, options.title, map_kind(options.kind)),
+    callback,
+    error,
+  );
+}
+
+/// Shows a directory picker and grants the file system APIs access to the selected directory,
+/// resolving its path. The directory is also saved to the settings if `persist` is set,
+/// so the access is kept across sessions.
+///
+/// Rejects without showing the picker if the `tauri.fs.dynamicScope` config is disabled.
+#[cfg(pick_scope_folder)]
+pub fn pick_scope_folder<T: 'static>(
+  webview: &mut WebView<'_, T>,
+  persist: bool,
+  callback: String,
+  error: String,
+) -> crate::Result<()> {
+  crate::execute_promise_sync(
+    webview,
+    move || {
+      if !crate::api::config::get()?.tauri.fs.dynamic_scope {
+        return Err(anyhow::anyhow!(
+          "granting access to more directories is disabled by the tauri.fs.dynamicScope config"
+        ));
+      }
+      let dir = match pick_folder(None::<String>)? {
+        Response::Okay(dir) => crate::api::scope::grant(dir)?,
+        _ => return Err(crate::api::Error::Dialog("user cancelled".into()).into()),
+      };
+      let dir = dir.to_string_lossy().to_string();
+      if persist {
+        let mut settings = crate::settings::read_settings()?;
+        if !settings.granted_folders.contains(&dir) {
+          settings.granted_folders.push(dir.clone());
+          crate::settings::write_settings(settings)?;
+        }
+      }
+      Ok(dir)
+    },
+    callback,
+    error,
+  )?;
+  Ok(())
+}
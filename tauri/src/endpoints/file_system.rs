@@ -7,11 +7,174 @@ use tauri_api::path::resolve_path;
 use std::fs;
 use std::fs::File;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+#[cfg(dir_size)]
+use once_cell::sync::Lazy;
+#[cfg(dir_size)]
+use std::collections::HashMap;
+#[cfg(dir_size)]
+use std::path::Component;
+#[cfg(dir_size)]
+use std::sync::{
+  atomic::{AtomicBool, Ordering},
+  Arc, Mutex,
+};
+
+#[cfg(dir_size)]
+use super::cmd::AppDirectory;
 use super::cmd::{DirOperationOptions, FileOperationOptions};
 
+/// Checks that the given path is inside the `tauri.fs.scope` directories
+/// or the directories granted by the user, if the scope is set.
+///
+/// The paths that don't exist yet, e.g. the files to write,
+/// are checked by their closest existing ancestor,
+/// so the paths with `..` components are rejected since they could escape it.
+#[cfg(any(
+  read_text_file,
+  read_binary_file,
+  write_file,
+  write_binary_file,
+  read_dir,
+  copy_file,
+  create_dir,
+  remove_dir,
+  remove_file,
+  rename_file,
+  watch,
+  hash_file,
+  http_request
+))]
+pub(crate) fn check_scope(path: &Path) -> crate::Result<()> {
+  let allowed = match allowed_dirs()? {
+    Some(allowed) => allowed,
+    None => return Ok(()),
+  };
+  let allowed = match existing_ancestor(path) {
+    Some(existing) => tauri_api::scope::is_allowed(&existing, &allowed),
+    None => false,
+  };
+  if allowed {
+    Ok(())
+  } else {
+    Err(anyhow::anyhow!(
+      "the path {} is not allowed by the file system scope",
+      path.display()
+    ))
+  }
+}
+
+/// Gets the closest existing ancestor of the given path, or `None` if it has `..` components,
+/// e.g. `<scope>/new/../../outside` would be checked by `<scope>` otherwise.
+///
+/// The dangling symbolic links are followed to their target, since writing through
+/// `<scope>/link` would create the file outside of the scope.
+#[cfg(any(
+  test,
+  read_text_file,
+  read_binary_file,
+  write_file,
+  write_binary_file,
+  read_dir,
+  copy_file,
+  create_dir,
+  remove_dir,
+  remove_file,
+  rename_file,
+  watch,
+  hash_file,
+  http_request
+))]
+fn existing_ancestor(path: &Path) -> Option<PathBuf> {
+  let mut path = path.to_path_buf();
+  // the links are followed a limited number of times, so a link cycle is rejected
+  for _ in 0..MAX_SYMLINKS {
+    if path
+      .components()
+      .any(|component| component == std::path::Component::ParentDir)
+    {
+      return None;
+    }
+    // `exists` follows the links, which would skip a dangling link and check its parent instead
+    let ancestor = path
+      .ancestors()
+      .find(|ancestor| ancestor.symlink_metadata().is_ok())
+      .unwrap_or(&path);
+    if ancestor.exists() || ancestor.symlink_metadata().is_err() {
+      return Some(ancestor.to_path_buf());
+    }
+    let target = fs::read_link(ancestor).ok()?;
+    let rest = path.strip_prefix(ancestor).ok()?.to_path_buf();
+    path = ancestor
+      .parent()
+      .unwrap_or_else(|| Path::new(""))
+      .join(target)
+      .join(rest);
+  }
+  None
+}
+
+/// The maximum number of symbolic links followed by `existing_ancestor`.
+#[cfg(any(
+  test,
+  read_text_file,
+  read_binary_file,
+  write_file,
+  write_binary_file,
+  read_dir,
+  copy_file,
+  create_dir,
+  remove_dir,
+  remove_file,
+  rename_file,
+  watch,
+  hash_file,
+  http_request
+))]
+const MAX_SYMLINKS: usize = 40;
+
+/// Gets the `tauri.fs.scope` directories and the directories granted by the user,
+/// or `None` if the scope isn't set and every path is allowed.
+#[cfg(any(
+  read_text_file,
+  read_binary_file,
+  write_file,
+  write_binary_file,
+  read_dir,
+  copy_file,
+  create_dir,
+  remove_dir,
+  remove_file,
+  rename_file,
+  watch,
+  hash_file,
+  http_request
+))]
+fn allowed_dirs() -> crate::Result<Option<Vec<PathBuf>>> {
+  let scope = &tauri_api::config::get()?.tauri.fs.scope;
+  if scope.is_empty() {
+    return Ok(None);
+  }
+  let mut allowed: Vec<PathBuf> = scope.iter().map(PathBuf::from).collect();
+  allowed.extend(tauri_api::scope::granted());
+  allowed.extend(
+    crate::settings::read_settings()?
+      .granted_folders
+      .into_iter()
+      .map(PathBuf::from),
+  );
+  Ok(Some(allowed))
+}
+
+/// How many subdirectory levels the recursive `readDir` reads if the `maxDepth` option isn't set,
+/// so huge trees aren't traversed by accident.
+#[cfg(read_dir)]
+const DEFAULT_READ_DIR_DEPTH: usize = 16;
+
 /// Reads a directory.
+///
+/// The entries outside the file system scope, e.g. symbolic links to other directories, are skipped.
 #[cfg(read_dir)]
 pub fn read_dir<T: 'static>(
   webview: &mut WebView<'_, T>,
@@ -23,12 +186,27 @@ pub fn read_dir<T: 'static>(
   crate::execute_promise(
     webview,
     move || {
-      let (recursive, dir) = if let Some(options_value) = options {
-        (options_value.recursive, options_value.dir)
+      let (recursive, dir, max_depth) = if let Some(options_value) = options {
+        (
+          options_value.recursive,
+          options_value.dir,
+          options_value.max_depth,
+        )
       } else {
-        (false, None)
+        (false, None, None)
       };
-      dir::read_dir(resolve_path(path, dir)?, recursive)
+      let resolved_path = resolve_path(path, dir)?;
+      check_scope(&resolved_path)?;
+      let depth = if recursive {
+        max_depth.unwrap_or(DEFAULT_READ_DIR_DEPTH)
+      } else {
+        0
+      };
+      let allowed = allowed_dirs()?;
+      dir::read_dir_with_depth(resolved_path, depth, &|entry| match &allowed {
+        Some(allowed) => tauri_api::scope::is_allowed(entry, allowed),
+        None => true,
+      })
     },
     callback,
     error,
@@ -55,6 +233,8 @@ pub fn copy_file<T: 'static>(
         ),
         None => (source, destination),
       };
+      check_scope(&src)?;
+      check_scope(&dest)?;
       fs::copy(src, dest).map_err(|e| e.into())
     },
     callback,
@@ -80,6 +260,7 @@ pub fn create_dir<T: 'static>(
         (false, None)
       };
       let resolved_path = resolve_path(path, dir)?;
+      check_scope(&resolved_path)?;
       let response = if recursive {
         fs::create_dir_all(resolved_path)
       } else {
@@ -111,6 +292,7 @@ pub fn remove_dir<T: 'static>(
         (false, None)
       };
       let resolved_path = resolve_path(path, dir)?;
+      check_scope(&resolved_path)?;
       let response = if recursive {
         fs::remove_dir_all(resolved_path)
       } else {
@@ -137,6 +319,7 @@ pub fn remove_file<T: 'static>(
     webview,
     move || {
       let resolved_path = resolve_path(path, options.and_then(|o| o.dir))?;
+      check_scope(&resolved_path)?;
       fs::remove_file(resolved_path).map_err(|e| e.into())
     },
     callback,
@@ -164,6 +347,8 @@ pub fn rename_file<T: 'static>(
         ),
         None => (old_path, new_path),
       };
+      check_scope(&old)?;
+      check_scope(&new)?;
       fs::rename(old, new).map_err(|e| e.into())
     },
     callback,
@@ -184,7 +369,9 @@ pub fn write_file<T: 'static>(
   crate::execute_promise(
     webview,
     move || {
-      File::create(resolve_path(path, options.and_then(|o| o.dir))?)
+      let resolved_path = resolve_path(path, options.and_then(|o| o.dir))?;
+      check_scope(&resolved_path)?;
+      File::create(resolved_path)
         .map_err(|e| e.into())
         .and_then(|mut f| f.write_all(contents.as_bytes()).map_err(|err| err.into()))
     },
@@ -193,7 +380,13 @@ pub fn write_file<T: 'static>(
   );
 }
 
-/// Writes a binary file.
+/// The maximum size of the binary files read and written with the base64 encoded contents,
+/// so a huge base64 string isn't evaluated on the webview.
+#[cfg(any(read_binary_file, write_binary_file))]
+const MAX_BINARY_FILE_SIZE: u64 = 10 * 1024 * 1024;
+
+/// Writes a binary file from its base64 encoded contents.
+/// Contents larger than `MAX_BINARY_FILE_SIZE` are rejected.
 #[cfg(write_binary_file)]
 pub fn write_binary_file<T: 'static>(
   webview: &mut WebView<'_, T>,
@@ -206,10 +399,19 @@ pub fn write_binary_file<T: 'static>(
   crate::execute_promise(
     webview,
     move || {
+      // the base64 encoding takes 4 bytes for every 3 bytes of contents
+      if contents.len() as u64 / 4 * 3 > MAX_BINARY_FILE_SIZE {
+        return Err(anyhow::anyhow!(
+          "the contents are larger than the {} bytes limit of writeBinaryFile",
+          MAX_BINARY_FILE_SIZE
+        ));
+      }
       base64::decode(contents)
         .map_err(|e| e.into())
         .and_then(|c| {
-          File::create(resolve_path(path, options.and_then(|o| o.dir))?)
+          let resolved_path = resolve_path(path, options.and_then(|o| o.dir))?;
+          check_scope(&resolved_path)?;
+          File::create(resolved_path)
             .map_err(|e| e.into())
             .and_then(|mut f| f.write_all(&c).map_err(|err| err.into()))
         })
@@ -230,13 +432,19 @@ pub fn read_text_file<T: 'static>(
 ) {
   crate::execute_promise(
     webview,
-    move || file::read_string(resolve_path(path, options.and_then(|o| o.dir))?),
+    move || {
+      let resolved_path = resolve_path(path, options.and_then(|o| o.dir))?;
+      check_scope(&resolved_path)?;
+      file::read_string(resolved_path)
+    },
     callback,
     error,
   );
 }
 
-/// Reads a binary file.
+/// Reads a binary file, resolving its base64 encoded contents.
+/// Files larger than `MAX_BINARY_FILE_SIZE` are rejected; they should be streamed to a file
+/// with the http `saveTo` option or read on the Rust side instead.
 #[cfg(read_binary_file)]
 pub fn read_binary_file<T: 'static>(
   webview: &mut WebView<'_, T>,
@@ -247,7 +455,43 @@ pub fn read_binary_file<T: 'static>(
 ) {
   crate::execute_promise(
     webview,
-    move || file::read_binary(resolve_path(path, options.and_then(|o| o.dir))?),
+    move || {
+      let resolved_path = resolve_path(path, options.and_then(|o| o.dir))?;
+      check_scope(&resolved_path)?;
+      let size = fs::metadata(&resolved_path)?.len();
+      if size > MAX_BINARY_FILE_SIZE {
+        return Err(anyhow::anyhow!(
+          "{} is larger than the {} bytes limit of readBinaryFile; stream it with the http saveTo option instead",
+          resolved_path.display(),
+          MAX_BINARY_FILE_SIZE
+        ));
+      }
+      file::read_binary(resolved_path).map(|contents| base64::encode(&contents))
+    },
+    callback,
+    error,
+  );
+}
+
+/// Computes the hex digest of a file with the given algorithm: `sha256`, `md5` or `blake3`.
+/// The file is read in chunks on the thread pool, so large files don't block the UI.
+#[cfg(hash_file)]
+pub fn hash_file<T: 'static>(
+  webview: &mut WebView<'_, T>,
+  path: PathBuf,
+  algo: String,
+  options: Option<FileOperationOptions>,
+  callback: String,
+  error: String,
+) {
+  crate::execute_promise(
+    webview,
+    move || {
+      let algorithm = algo.parse::<file::HashAlgorithm>()?;
+      let resolved_path = resolve_path(path, options.and_then(|o| o.dir))?;
+      check_scope(&resolved_path)?;
+      file::hash(resolved_path, algorithm)
+    },
     callback,
     error,
   );
@@ -256,7 +500,51 @@ pub fn read_binary_file<T: 'static>(
 // test webview functionality.
 #[cfg(test)]
 mod test {
-  // use super::*;
+  use super::*;
+
+  #[test]
+  // check that the missing paths are checked by their closest existing ancestor
+  fn check_existing_ancestor() {
+    assert_eq!(
+      existing_ancestor(Path::new("test/fixture/missing/file.txt")),
+      Some(PathBuf::from("test/fixture"))
+    );
+    assert_eq!(
+      existing_ancestor(Path::new("test/fixture/test.txt")),
+      Some(PathBuf::from("test/fixture/test.txt"))
+    );
+    assert_eq!(
+      existing_ancestor(Path::new("test/fixture/missing/../../../Cargo.toml")),
+      None
+    );
+  }
+
+  #[test]
+  #[cfg(unix)]
+  // check that a dangling symlink inside the scope can't be used to write outside of it
+  fn check_dangling_symlink() {
+    let root = std::env::temp_dir().join(format!("tauri-scope-{}", std::process::id()));
+    let scope = root.join("scope");
+    let outside = root.join("outside");
+    fs::create_dir_all(&scope).expect("failed to create the scope dir");
+    fs::create_dir_all(&outside).expect("failed to create the outside dir");
+    let escaping_link = scope.join("escaping.txt");
+    std::os::unix::fs::symlink(outside.join("missing.txt"), &escaping_link)
+      .expect("failed to create the escaping link");
+    let inner_link = scope.join("inner.txt");
+    std::os::unix::fs::symlink(scope.join("missing.txt"), &inner_link)
+      .expect("failed to create the inner link");
+
+    let escaping = existing_ancestor(&escaping_link).expect("expected an existing ancestor");
+    assert_eq!(escaping, outside);
+    assert!(!tauri_api::scope::is_allowed(&escaping, &[&scope]));
+    let inner = existing_ancestor(&inner_link).expect("expected an existing ancestor");
+    assert_eq!(inner, scope);
+    assert!(tauri_api::scope::is_allowed(&inner, &[&scope]));
+
+    fs::remove_dir_all(&root).expect("failed to remove the test dirs");
+  }
+
   // use web_view::*;
 
   // create a makeshift webview
@@ -316,3 +604,154 @@ mod test {
     Ok(())
   } */
 }
+
+/// Watches the given files or directories, emitting the `file-change` event with the changes.
+/// The paths must exist and be allowed by the file system scope. Resolves the watcher id.
+#[cfg(watch)]
+pub fn watch<T: 'static>(
+  webview: &mut WebView<'_, T>,
+  paths: Vec<PathBuf>,
+  recursive: bool,
+  callback: String,
+  error: String,
+) {
+  let handle = webview.handle();
+  crate::execute_promise(
+    webview,
+    move || {
+      for path in &paths {
+        if !path.exists() {
+          return Err(anyhow::anyhow!("the path {} doesn't exist", path.display()));
+        }
+        check_scope(path)?;
+      }
+      tauri_api::watch::watch(paths, recursive, move |change| {
+        if let Err(e) = crate::event::emit(&handle, "file-change", Some(change)) {
+          eprintln!("failed to emit the file-change event: {}", e);
+        }
+      })
+    },
+    callback,
+    error,
+  );
+}
+
+/// Stops the watcher with the given id.
+#[cfg(watch)]
+pub fn unwatch<T: 'static>(webview: &mut WebView<'_, T>, id: u32, callback: String, error: String) {
+  crate::execute_promise(
+    webview,
+    move || tauri_api::watch::unwatch(id),
+    callback,
+    error,
+  );
+}
+
+/// The cancellation flags of the running `dirSize` computations, by id.
+#[cfg(dir_size)]
+static DIR_SIZE_JOBS: Lazy<Mutex<HashMap<u32, Arc<AtomicBool>>>> =
+  Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Resolves the total size in bytes of the files in the given app directory,
+/// or in the `path` inside it. The computation can be cancelled with `cancel_dir_size`.
+#[cfg(dir_size)]
+pub fn dir_size<T: 'static>(
+  webview: &mut WebView<'_, T>,
+  id: u32,
+  base: AppDirectory,
+  path: Option<PathBuf>,
+  callback: String,
+  error: String,
+) {
+  crate::execute_promise(
+    webview,
+    move || {
+      let base_dir = match base {
+        AppDirectory::AppData => tauri_api::path::app_dir(),
+        AppDirectory::AppCache => tauri_api::path::app_cache_dir(),
+      }
+      .ok_or_else(|| anyhow::anyhow!("unable to determine the app directory path"))?;
+      let resolved_path = match path {
+        Some(path) => {
+          let escapes = path.components().any(|component| match component {
+            Component::Normal(_) | Component::CurDir => false,
+            _ => true,
+          });
+          if escapes {
+            return Err(anyhow::anyhow!(
+              "the path must be relative to the app directory"
+            ));
+          }
+          base_dir.join(path)
+        }
+        None => base_dir.clone(),
+      };
+      if !resolved_path.exists() {
+        return Ok(0);
+      }
+      if !tauri_api::scope::is_allowed(&resolved_path, &[&base_dir]) {
+        return Err(anyhow::anyhow!(
+          "the path {} is outside the app directory",
+          resolved_path.display()
+        ));
+      }
+      let cancelled = Arc::new(AtomicBool::new(false));
+      {
+        let mut jobs = DIR_SIZE_JOBS.lock().expect("Failed to lock dir size jobs");
+        if jobs.contains_key(&id) {
+          return Err(anyhow::anyhow!(
+            "a directory size computation with the id {} is already running",
+            id
+          ));
+        }
+        jobs.insert(id, cancelled.clone());
+      }
+      let size = dir::size(resolved_path, &cancelled);
+      DIR_SIZE_JOBS
+        .lock()
+        .expect("Failed to lock dir size jobs")
+        .remove(&id);
+      size
+    },
+    callback,
+    error,
+  );
+}
+
+/// Cancels the `dirSize` computation with the given id, rejecting its promise.
+/// Does nothing if the computation has already finished.
+#[cfg(dir_size)]
+pub fn cancel_dir_size(id: u32) {
+  if let Some(cancelled) = DIR_SIZE_JOBS
+    .lock()
+    .expect("Failed to lock dir size jobs")
+    .get(&id)
+  {
+    cancelled.store(true, Ordering::Relaxed);
+  }
+}
+
+/// Resolves the free and total space of the volume containing the given path.
+/// The path must be allowed by the `tauri.fs.diskSpaceScope` config.
+#[cfg(disk_space)]
+pub fn disk_space<T: 'static>(
+  webview: &mut WebView<'_, T>,
+  path: PathBuf,
+  callback: String,
+  error: String,
+) {
+  crate::execute_promise(
+    webview,
+    move || {
+      let config = tauri_api::config::get()?;
+      if !tauri_api::scope::is_allowed(&path, &config.tauri.fs.disk_space_scope) {
+        return Err(anyhow::anyhow!(
+          "the path is not allowed by the disk space scope"
+        ));
+      }
+      tauri_api::disk::space(path)
+    },
+    callback,
+    error,
+  );
+}
@@ -1,12 +1,66 @@
+#[cfg(http_request)]
+use std::path::Path;
+#[cfg(http_request)]
+use tauri_api::config::get;
+#[cfg(set_proxy)]
+use tauri_api::config::ProxyConfig;
+#[cfg(http_request)]
 use tauri_api::http::{make_request as request, HttpRequestOptions};
 use web_view::WebView;
 
 /// Makes an HTTP request and resolves the response to the webview
+///
+/// The `saveTo` file must be allowed by the file system scope, and the file writes must be whitelisted.
+#[cfg(http_request)]
 pub fn make_request<T: 'static>(
   webview: &mut WebView<'_, T>,
-  options: HttpRequestOptions,
+  mut options: HttpRequestOptions,
   callback: String,
   error: String,
 ) {
-  crate::execute_promise(webview, move || request(options), callback, error);
+  crate::execute_promise(
+    webview,
+    move || {
+      options.max_response_bytes = get()?.tauri.http.max_response_bytes;
+      if let Some(save_to) = &options.save_to {
+        check_save_to(save_to)?;
+      }
+      request(options)
+    },
+    callback,
+    error,
+  );
+}
+
+/// Checks that the response can be written to the given file.
+#[cfg(all(http_request, any(write_file, write_binary_file)))]
+fn check_save_to(path: &Path) -> crate::Result<()> {
+  super::file_system::check_scope(path)
+}
+
+/// Rejects the `saveTo` option, since the file writes aren't whitelisted.
+#[cfg(all(http_request, not(any(write_file, write_binary_file))))]
+fn check_save_to(_path: &Path) -> crate::Result<()> {
+  Err(anyhow::anyhow!(
+    "the saveTo option needs the 'writeFile' or 'writeBinaryFile' API to be whitelisted"
+  ))
+}
+
+/// Sets the proxy used by the httpRequest API, or clears it with `None` to use the proxy environment variables.
+///
+/// The webview reads the proxy when it's built, so its requests keep using the `tauri.network.proxy` config.
+#[cfg(set_proxy)]
+pub fn set_proxy<T: 'static>(
+  webview: &mut WebView<'_, T>,
+  url: Option<String>,
+  bypass: Vec<String>,
+  callback: String,
+  error: String,
+) {
+  crate::execute_promise(
+    webview,
+    move || tauri_api::http::set_proxy(url.map(|url| ProxyConfig { url, bypass })),
+    callback,
+    error,
+  );
 }
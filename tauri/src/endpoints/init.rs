@@ -1,8 +1,52 @@
+#[cfg(event)]
+use once_cell::sync::Lazy;
+use once_cell::sync::OnceCell;
+use tauri_api::config::{get, ContextMenu, ExternalLinks, Theme};
+
+/// The salt used by the init script to emit webview-originated events (focus, blur...) to JS listeners.
+#[cfg(event)]
+static WINDOW_EVENTS_SALT: Lazy<String> = Lazy::new(crate::salt::generate_static);
+
+/// The name of the JS function that toggles the loading overlay.
+#[cfg(set_loading)]
+pub(crate) const LOADING_OVERLAY_FUNCTION_NAME: &str = "__TAURI_LOADING_OVERLAY__";
+
+/// The data exposed to the frontend as `window.__TAURI_INIT__`, serialized as JSON.
+static INIT_DATA: OnceCell<String> = OnceCell::new();
+
+/// Sets the data exposed to the frontend as `window.__TAURI_INIT__` by the init script,
+/// so it's set again on every document the bridge initializes, e.g. after the splashscreen.
+pub(crate) fn set_init_data(data: String) {
+  let _ = INIT_DATA.set(data);
+}
+
 pub fn init() -> crate::Result<String> {
-  #[cfg(not(event))]
-  return Ok(String::from(""));
+  let mut init_script = String::new();
+  if let Some(data) = INIT_DATA.get() {
+    init_script.push_str(&format!("window.__TAURI_INIT__ = Object.freeze({});", data));
+  }
   #[cfg(event)]
-  return Ok(format!(
+  init_script.push_str(&event_init()?);
+  init_script.push_str(&theme_init()?);
+  init_script.push_str(&spellcheck_init()?);
+  init_script.push_str(&context_menu_init()?);
+  init_script.push_str(&close_confirmation_init()?);
+  #[cfg(logger)]
+  init_script.push_str(&console_forwarding_init()?);
+  init_script.push_str(&external_links_init()?);
+  init_script.push_str(&navigation_allowlist_init()?);
+  init_script.push_str(&request_interceptors_init()?);
+  init_script.push_str(&request_headers_init()?);
+  init_script.push_str(&mixed_content_init());
+  #[cfg(set_loading)]
+  init_script.push_str(&loading_overlay_init());
+  Ok(init_script)
+}
+
+/// Returns the JS that sets up the event system.
+#[cfg(event)]
+fn event_init() -> crate::Result<String> {
+  Ok(format!(
     "
       window['{queue}'] = [];
       window['{fn}'] = function (payload, salt, ignoreQueue) {{
@@ -28,9 +72,525 @@ pub fn init() -> crate::Result<String> {
         }})
       }}
     }}
+    {window_events}
     ",
     fn = crate::event::emit_function_name(),
     queue = crate::event::event_queue_object_name(),
-    listeners = crate::event::event_listeners_object_name()
-  ));
+    listeners = crate::event::event_listeners_object_name(),
+    window_events = window_events()?
+  ))
+}
+
+/// Returns the JS that emits the given event to the JS listeners.
+/// The payload must be a JS expression, e.g. a serialized JSON value.
+#[cfg(event)]
+pub(crate) fn emit_event_js(event: &str, payload: &str) -> String {
+  format!(
+    "window['{fn}']({{ type: '{event}', payload: {payload} }}, '{salt}', true)",
+    fn = crate::event::emit_function_name(),
+    event = event,
+    payload = payload,
+    salt = *WINDOW_EVENTS_SALT
+  )
+}
+
+/// Returns the JS that forwards the webview window events to the event system.
+#[cfg(event)]
+fn window_events() -> crate::Result<String> {
+  let theme_events = if get()?.tauri.window.theme.is_none() {
+    format!(
+      "
+        if (window.matchMedia) {{
+          window.matchMedia('(prefers-color-scheme: dark)').addListener(function (e) {{
+            window['{fn}']({{ type: 'theme-changed', payload: e.matches ? 'dark' : 'light' }}, '{salt}', true)
+          }})
+        }}
+      ",
+      fn = crate::event::emit_function_name(),
+      salt = *WINDOW_EVENTS_SALT
+    )
+  } else {
+    String::from("")
+  };
+  Ok(format!(
+    "
+      window.addEventListener('focus', function () {{
+        window['{fn}']({{ type: 'focus', payload: null }}, '{salt}', true)
+      }})
+      window.addEventListener('blur', function () {{
+        window['{fn}']({{ type: 'blur', payload: null }}, '{salt}', true)
+      }})
+      {theme_events}
+      {scale_events}
+    ",
+    fn = crate::event::emit_function_name(),
+    salt = *WINDOW_EVENTS_SALT,
+    theme_events = theme_events,
+    scale_events = scale_events()
+  ))
+}
+
+/// Returns the JS that emits the `scale-changed` event with the new scale factor
+/// and the physical inner size when the window moves to a display with another DPI.
+///
+/// The webview backend has no DPI callback, so the change is detected with a resolution media query,
+/// which only matches the current scale factor and is replaced after every change.
+#[cfg(event)]
+fn scale_events() -> String {
+  format!(
+    "
+      if (window.matchMedia) {{
+        (function watchScale() {{
+          var query = window.matchMedia('(resolution: ' + window.devicePixelRatio + 'dppx)')
+          function onChange() {{
+            query.removeListener(onChange)
+            {emit}
+            watchScale()
+          }}
+          query.addListener(onChange)
+        }})()
+      }}
+    ",
+    emit = emit_event_js(
+      "scale-changed",
+      "{ scaleFactor: window.devicePixelRatio, width: Math.round(window.innerWidth * window.devicePixelRatio), height: Math.round(window.innerHeight * window.devicePixelRatio) }"
+    )
+  )
+}
+
+/// Returns the JS that forces the `tauri.window.theme` config, if it's set.
+fn theme_init() -> crate::Result<String> {
+  let theme = match get()?.tauri.window.theme {
+    Some(Theme::Light) => "light",
+    Some(Theme::Dark) => "dark",
+    None => return Ok(String::from("")),
+  };
+  Ok(format!(
+    "document.documentElement.style.colorScheme = '{}';",
+    theme
+  ))
+}
+
+/// Returns the JS that applies the `tauri.window.spellcheck` config, if it's set.
+fn spellcheck_init() -> crate::Result<String> {
+  Ok(match get()?.tauri.window.spellcheck {
+    Some(enabled) => spellcheck_js(enabled),
+    None => String::from(""),
+  })
+}
+
+/// Returns the JS that toggles the spellchecker.
+/// The `spellcheck` attribute is inherited, so setting it on the document element
+/// applies to every editable element that doesn't override it.
+pub(crate) fn spellcheck_js(enabled: bool) -> String {
+  format!("document.documentElement.spellcheck = {};", enabled)
+}
+
+/// The name of the JS flag set by the `setCloseConfirmation` API.
+pub(crate) const CLOSE_CONFIRMATION_FLAG_NAME: &str = "__TAURI_CLOSE_CONFIRMATION__";
+
+/// Returns the JS that asks for a confirmation before unloading the content,
+/// if the `tauri.window.confirmOnClose` config is set.
+fn close_confirmation_init() -> crate::Result<String> {
+  if !get()?.tauri.window.confirm_on_close {
+    return Ok(String::from(""));
+  }
+  Ok(format!(
+    "
+      window['{flag}'] = true
+      window.addEventListener('beforeunload', function (e) {{
+        if (window['{flag}']) {{
+          e.preventDefault()
+          e.returnValue = ''
+        }}
+      }})
+    ",
+    flag = CLOSE_CONFIRMATION_FLAG_NAME
+  ))
+}
+
+/// The JS that forwards the console messages and the uncaught errors to the app log
+/// with their level and source location.
+/// The original console methods are still called, so the devtools keep showing the messages.
+#[cfg(any(logger, test))]
+pub(crate) const CONSOLE_FORWARDING_JS: &str = r#"
+      (function () {
+        function stringify(arg) {
+          if (typeof arg === 'string') return arg
+          if (arg instanceof Error) return arg.stack || String(arg)
+          try {
+            return JSON.stringify(arg)
+          } catch (e) {
+            return String(arg)
+          }
+        }
+        function forward(level, message, source) {
+          window.external.invoke(JSON.stringify({ cmd: 'log', level: level, message: message, source: source }))
+        }
+        // the stack frame of the console method caller
+        function callerSource() {
+          const frames = (new Error().stack || '').split('\n')
+          const frame = frames[0] === 'Error' ? frames[3] : frames[2]
+          return frame ? frame.trim().replace(/^at /, '') : null
+        }
+        const levels = { debug: 'debug', log: 'info', info: 'info', warn: 'warn', error: 'error' }
+        Object.keys(levels).forEach(function (method) {
+          const original = console[method]
+          console[method] = function () {
+            forward(levels[method], Array.prototype.map.call(arguments, stringify).join(' '), callerSource())
+            return original.apply(console, arguments)
+          }
+        })
+        window.addEventListener('error', function (e) {
+          forward('error', 'Uncaught ' + stringify(e.error || e.message), e.filename ? e.filename + ':' + e.lineno + ':' + e.colno : null)
+        })
+        window.addEventListener('unhandledrejection', function (e) {
+          forward('error', 'Unhandled rejection ' + stringify(e.reason), null)
+        })
+      })()
+    "#;
+
+/// Returns the JS that forwards the console messages to the app log,
+/// if the `tauri.window.forwardConsole` config is set.
+#[cfg(logger)]
+fn console_forwarding_init() -> crate::Result<String> {
+  if !get()?.tauri.window.forward_console {
+    return Ok(String::from(""));
+  }
+  Ok(String::from(CONSOLE_FORWARDING_JS))
+}
+
+/// Returns the JS that applies the `tauri.window.contextMenu` config.
+fn context_menu_init() -> crate::Result<String> {
+  let on_context_menu = match get()?.tauri.window.context_menu {
+    ContextMenu::Default => return Ok(String::from("")),
+    ContextMenu::None => String::from(""),
+    ContextMenu::Custom => context_menu_event_js(),
+  };
+  Ok(format!(
+    "
+      window.addEventListener('contextmenu', function (e) {{
+        e.preventDefault()
+        {on_context_menu}
+      }})
+    ",
+    on_context_menu = on_context_menu
+  ))
+}
+
+/// Returns the JS that emits the `context-menu` event with the click coordinates.
+#[cfg(event)]
+fn context_menu_event_js() -> String {
+  emit_event_js("context-menu", "{ x: e.clientX, y: e.clientY }")
+}
+
+/// Without the event API the `context-menu` event can't be emitted, so the menu is only suppressed.
+#[cfg(not(event))]
+fn context_menu_event_js() -> String {
+  eprintln!("the `context-menu` event needs the event API; the context menu is only suppressed");
+  String::from("")
+}
+
+/// Returns the JS that enforces the `tauri.window.externalLinks` policy
+/// on the clicked links to other origins.
+fn external_links_init() -> crate::Result<String> {
+  let open_link = match get()?.tauri.window.external_links {
+    ExternalLinks::Allow => return Ok(String::from("")),
+    ExternalLinks::Block => "",
+    ExternalLinks::Browser => {
+      "window.external.invoke(JSON.stringify({ cmd: 'openExternalLink', uri: target.href }))"
+    }
+  };
+  Ok(format!(
+    "
+      document.addEventListener('click', function (e) {{
+        var target = e.target
+        while (target && target.tagName !== 'A') {{
+          target = target.parentElement
+        }}
+        if (
+          target &&
+          target.href &&
+          /^(https?|mailto):/.test(target.protocol) &&
+          target.host !== window.location.host
+        ) {{
+          e.preventDefault()
+          {open_link}
+        }}
+      }}, true)
+    ",
+    open_link = open_link
+  ))
+}
+
+/// Returns the JS that cancels the link, form and `window.open` navigations to the URLs
+/// outside the app origin and the `tauri.security.navigationAllowlist`.
+///
+/// It's a best-effort guard: the webview backend doesn't expose a navigation hook,
+/// so the `location` changes, the meta refreshes and the redirects aren't blocked.
+fn navigation_allowlist_init() -> crate::Result<String> {
+  let allowlist = &get()?.tauri.security.navigation_allowlist;
+  if allowlist.is_empty() {
+    return Ok(String::from(""));
+  }
+  Ok(format!(
+    r#"
+      (function () {{
+        function escape(value) {{
+          return value.replace(/[.+?^${{}}()|[\]\\]/g, '\\$&')
+        }}
+        // the origin is matched whole, so a `*` there can't cross the host boundary,
+        // and the path is a prefix ending at a segment boundary unless it has a `*`
+        var patterns = {patterns}.map(function (pattern) {{
+          var parts = /^([^:/?#]+:\/\/[^/?#]*)(.*)$/.exec(pattern) || ['', pattern, '']
+          var origin = escape(parts[1]).replace(/\*/g, '[^/?#@]*')
+          var path = escape(parts[2])
+          var end = path.indexOf('*') !== -1 ? '$' : /[/?#]$/.test(path) ? '' : '(?:[/?#]|$)'
+          return new RegExp('^' + origin + path.replace(/\*/g, '.*') + end)
+        }})
+        function isAllowed(url) {{
+          var a = document.createElement('a')
+          a.href = url
+          if (a.protocol === window.location.protocol && a.host === window.location.host) {{
+            return true
+          }}
+          return patterns.some(function (pattern) {{ return pattern.test(a.href) }})
+        }}
+        function block(url) {{
+          console.warn('blocked the navigation to ' + url)
+          window.external.invoke(JSON.stringify({{ cmd: 'navigationBlocked', url: url }}))
+        }}
+        document.addEventListener('click', function (e) {{
+          var target = e.target
+          while (target && target.tagName !== 'A') {{
+            target = target.parentElement
+          }}
+          if (!e.defaultPrevented && target && target.href && !isAllowed(target.href)) {{
+            e.preventDefault()
+            block(target.href)
+          }}
+        }}, true)
+        document.addEventListener('submit', function (e) {{
+          var action = e.target.action || window.location.href
+          if (!e.defaultPrevented && !isAllowed(action)) {{
+            e.preventDefault()
+            block(action)
+          }}
+        }}, true)
+        var open = window.open
+        window.open = function (url) {{
+          if (url && !isAllowed(url)) {{
+            block(url)
+            return null
+          }}
+          return open.apply(window, arguments)
+        }}
+      }})()
+    "#,
+    patterns = serde_json::to_string(allowlist)?
+  ))
+}
+
+/// Returns the JS that applies the `tauri.security.requestInterceptors` config,
+/// rewriting or blocking the matching `fetch` and `XMLHttpRequest` requests.
+///
+/// The webview backends don't expose a request interception hook,
+/// so the requests made by the document itself, e.g. the images and the scripts, aren't intercepted.
+fn request_interceptors_init() -> crate::Result<String> {
+  let interceptors = &get()?.tauri.security.request_interceptors;
+  if interceptors.is_empty() {
+    return Ok(String::from(""));
+  }
+  Ok(format!(
+    r#"
+      (function () {{
+        var interceptors = {interceptors}
+        // resolves the URL against the document, returning null if the request is blocked
+        function intercept(url) {{
+          var a = document.createElement('a')
+          a.href = url
+          var resolved = a.href
+          for (var i = 0; i < interceptors.length; i++) {{
+            var interceptor = interceptors[i]
+            if (resolved.indexOf(interceptor.prefix) === 0) {{
+              if (!interceptor.redirect) {{
+                console.warn('blocked the request to ' + resolved)
+                return null
+              }}
+              return interceptor.redirect + resolved.slice(interceptor.prefix.length)
+            }}
+          }}
+          return url
+        }}
+        if (window.fetch) {{
+          var originalFetch = window.fetch
+          window.fetch = function (input, init) {{
+            var isRequest = typeof Request !== 'undefined' && input instanceof Request
+            var url = intercept(isRequest ? input.url : String(input))
+            if (url === null) {{
+              return Promise.reject(new TypeError('the request was blocked by the requestInterceptors config'))
+            }}
+            return originalFetch.call(window, isRequest ? new Request(url, input) : url, init)
+          }}
+        }}
+        var originalOpen = XMLHttpRequest.prototype.open
+        XMLHttpRequest.prototype.open = function (method, url) {{
+          var intercepted = intercept(String(url))
+          var args = Array.prototype.slice.call(arguments)
+          // a blocked request is sent to an invalid URL, so it fails with the error event
+          args[1] = intercepted === null ? 'tauri-blocked:' : intercepted
+          return originalOpen.apply(this, args)
+        }}
+      }})()
+    "#,
+    interceptors = serde_json::to_string(interceptors)?
+  ))
+}
+
+/// Returns the JS that reports the `http` subresources and requests blocked on an `https` content,
+/// since the webview blocks them without an explanation, leaving blank images or failed requests.
+fn mixed_content_init() -> String {
+  String::from(
+    r#"
+      (function () {
+        if (window.location.protocol !== 'https:') return
+        function report(url) {
+          console.warn('the mixed content ' + url + ' was blocked: an https content can\'t load http resources; serve it over https')
+        }
+        function isInsecure(url) {
+          return typeof url === 'string' && url.indexOf('http:') === 0
+        }
+        // the resource errors don't bubble, so they're captured
+        window.addEventListener('error', function (e) {
+          var target = e.target
+          if (!target || target === window) return
+          var url = target.currentSrc || target.src || target.href
+          if (isInsecure(url)) report(url)
+        }, true)
+        if (window.fetch) {
+          var originalFetch = window.fetch
+          window.fetch = function (input, init) {
+            var url = typeof Request !== 'undefined' && input instanceof Request ? input.url : String(input)
+            if (isInsecure(url)) report(url)
+            return originalFetch.apply(window, arguments)
+          }
+        }
+        var originalOpen = XMLHttpRequest.prototype.open
+        XMLHttpRequest.prototype.open = function (method, url) {
+          if (isInsecure(String(url))) report(String(url))
+          return originalOpen.apply(this, arguments)
+        }
+      })()
+    "#,
+  )
+}
+
+/// The name of the JS object holding the headers added to the requests to the content origin.
+pub(crate) const REQUEST_HEADERS_NAME: &str = "__TAURI_REQUEST_HEADERS__";
+
+/// Returns the JS that adds the `build.requestHeaders` config, or the headers set with the
+/// `setRequestHeaders` API, to the `fetch` and `XMLHttpRequest` requests to the content origin.
+fn request_headers_init() -> crate::Result<String> {
+  let headers = &get()?.build.request_headers;
+  if headers.is_empty() && !cfg!(request_headers) {
+    return Ok(String::from(""));
+  }
+  Ok(format!(
+    r#"
+      (function () {{
+        window['{name}'] = {headers}
+        // the host includes the port, so with the protocol it's the full origin
+        function isSameOrigin(url) {{
+          var a = document.createElement('a')
+          a.href = url
+          return a.protocol === window.location.protocol && a.host === window.location.host
+        }}
+        if (window.fetch) {{
+          var originalFetch = window.fetch
+          window.fetch = function (input, init) {{
+            var isRequest = typeof Request !== 'undefined' && input instanceof Request
+            if (!isSameOrigin(isRequest ? input.url : String(input))) {{
+              return originalFetch.call(window, input, init)
+            }}
+            var request = new Request(input, init)
+            var extra = window['{name}']
+            Object.keys(extra).forEach(function (name) {{
+              if (!request.headers.has(name)) {{
+                request.headers.set(name, extra[name])
+              }}
+            }})
+            return originalFetch.call(window, request)
+          }}
+        }}
+        var originalOpen = XMLHttpRequest.prototype.open
+        var originalSetRequestHeader = XMLHttpRequest.prototype.setRequestHeader
+        var originalSend = XMLHttpRequest.prototype.send
+        XMLHttpRequest.prototype.open = function (method, url) {{
+          this.__tauriSameOrigin = isSameOrigin(String(url))
+          // opening the request again clears its headers
+          this.__tauriHeaders = {{}}
+          return originalOpen.apply(this, arguments)
+        }}
+        // the headers set by the app are tracked, since setRequestHeader appends to them instead of replacing them
+        XMLHttpRequest.prototype.setRequestHeader = function (name, value) {{
+          if (this.__tauriHeaders) {{
+            this.__tauriHeaders[String(name).toLowerCase()] = true
+          }}
+          return originalSetRequestHeader.apply(this, arguments)
+        }}
+        XMLHttpRequest.prototype.send = function () {{
+          if (this.__tauriSameOrigin) {{
+            var extra = window['{name}']
+            for (var name in extra) {{
+              if (!this.__tauriHeaders[name.toLowerCase()]) {{
+                originalSetRequestHeader.call(this, name, extra[name])
+              }}
+            }}
+          }}
+          return originalSend.apply(this, arguments)
+        }}
+      }})()
+    "#,
+    name = REQUEST_HEADERS_NAME,
+    headers = serde_json::to_string(headers)?
+  ))
+}
+
+/// Returns the JS that defines the loading overlay toggled by the `setLoading` API.
+/// The overlay is attached to the document element, so it doesn't depend on the app DOM.
+#[cfg(set_loading)]
+fn loading_overlay_init() -> String {
+  format!(
+    "
+      window['{name}'] = (function () {{
+        var overlay = null
+        var message = null
+        var timeout = null
+        function hide() {{
+          clearTimeout(timeout)
+          if (overlay) {{
+            overlay.style.display = 'none'
+          }}
+        }}
+        function show(text, timeoutMs) {{
+          if (!overlay) {{
+            overlay = document.createElement('div')
+            overlay.setAttribute('role', 'progressbar')
+            overlay.style.cssText = 'position: fixed; top: 0; left: 0; right: 0; bottom: 0; z-index: 2147483647; display: flex; align-items: center; justify-content: center; background: rgba(0, 0, 0, 0.4); color: #fff; font: 16px sans-serif'
+            message = document.createElement('span')
+            overlay.appendChild(message)
+            document.documentElement.appendChild(overlay)
+          }}
+          message.textContent = text || ''
+          overlay.style.display = 'flex'
+          clearTimeout(timeout)
+          timeout = setTimeout(hide, timeoutMs)
+        }}
+        return function (visible, text, timeoutMs) {{
+          visible ? show(text, timeoutMs) : hide()
+        }}
+      }})()
+    ",
+    name = LOADING_OVERLAY_FUNCTION_NAME
+  )
 }
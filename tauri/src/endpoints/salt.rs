@@ -1,3 +1,4 @@
+use crate::error::{ErrorCategory, ErrorPayload};
 use web_view::WebView;
 
 /// Validates a salt.
@@ -10,7 +11,7 @@ pub fn validate<T: 'static>(
   let response = if crate::salt::is_valid(salt) {
     Ok("Valid")
   } else {
-    Err("Invalid salt")
+    Err(ErrorPayload::new(ErrorCategory::Command, "Invalid salt"))
   };
   let callback_string = crate::api::rpc::format_callback_result(response, callback, error)?;
   webview.eval(callback_string.as_str())?;
@@ -0,0 +1,52 @@
+#[cfg(shell_open)]
+use std::path::PathBuf;
+#[cfg(shell_open)]
+use tauri_api::config::get;
+#[cfg(shell_open)]
+use tauri_api::scope::is_allowed;
+#[cfg(file_association)]
+use web_view::WebView;
+
+/// Opens the given path with the default application of the OS.
+/// The path must be allowed by the `tauri.shell.openScope` config.
+#[cfg(shell_open)]
+pub fn open(path: PathBuf) -> crate::Result<()> {
+  let config = get()?;
+  if !is_allowed(&path, &config.tauri.shell.open_scope) {
+    return Err(anyhow::anyhow!(
+      "the path is not allowed by the shell open scope"
+    ));
+  }
+  crate::spawn(move || {
+    // e.g. there's no default application for the path
+    if let Err(e) = tauri_api::shell::open(&path) {
+      eprintln!("failed to open {}: {}", path.display(), e);
+    }
+  });
+  Ok(())
+}
+
+/// Registers the app as the "open with" handler of the given file extension.
+/// Resolves once the OS registration is done, or rejects with a permission error
+/// if the platform requires an elevation the app doesn't have.
+#[cfg(file_association)]
+pub fn register_file_association<T: 'static>(
+  webview: &mut WebView<'_, T>,
+  extension: String,
+  description: Option<String>,
+  callback: String,
+  error: String,
+) {
+  crate::execute_promise(
+    webview,
+    move || {
+      let description = match description {
+        Some(description) => description,
+        None => format!("{} file", extension.trim_start_matches('.').to_uppercase()),
+      };
+      tauri_api::file_association::register(&extension, &description)
+    },
+    callback,
+    error,
+  );
+}
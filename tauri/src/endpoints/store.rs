@@ -0,0 +1,43 @@
+use serde_json::Value as JsonValue;
+use web_view::WebView;
+
+/// Gets a value from the app store.
+pub fn get<T: 'static>(webview: &mut WebView<'_, T>, key: String, callback: String, error: String) {
+  crate::execute_promise(
+    webview,
+    move || crate::store::get(key).map(|value| value.unwrap_or(JsonValue::Null)),
+    callback,
+    error,
+  );
+}
+
+/// Sets a value on the app store.
+pub fn set<T: 'static>(
+  webview: &mut WebView<'_, T>,
+  key: String,
+  value: JsonValue,
+  callback: String,
+  error: String,
+) {
+  crate::execute_promise(
+    webview,
+    move || crate::store::set(key, value),
+    callback,
+    error,
+  );
+}
+
+/// Deletes a key from the app store.
+pub fn delete<T: 'static>(
+  webview: &mut WebView<'_, T>,
+  key: String,
+  callback: String,
+  error: String,
+) {
+  crate::execute_promise(webview, move || crate::store::delete(key), callback, error);
+}
+
+/// Writes the app store to the file system.
+pub fn save<T: 'static>(webview: &mut WebView<'_, T>, callback: String, error: String) {
+  crate::execute_promise(webview, crate::store::save, callback, error);
+}
@@ -0,0 +1,614 @@
+#[cfg(activation_policy)]
+use super::cmd::ActivationPolicy;
+#[cfg(cookies)]
+use super::cmd::Cookie;
+#[cfg(clear_cache)]
+use super::cmd::StorageKind;
+#[cfg(request_headers)]
+use std::collections::HashMap;
+#[cfg(any(open, close_confirmation, theme, media_permissions))]
+use tauri_api::config::get;
+#[cfg(open)]
+use tauri_api::config::ExternalLinks;
+#[cfg(any(
+  focus,
+  request_headers,
+  activation_policy,
+  close_confirmation,
+  scale_factor,
+  theme,
+  set_loading,
+  set_badge,
+  visible_on_all_workspaces,
+  print,
+  cookies,
+  media_permissions,
+  clear_cache
+))]
+use web_view::WebView;
+
+/// The JS `{ category, message, details }` error object of the thrown `e` value,
+/// like the one the failed commands are rejected with.
+#[cfg(any(focus, scale_factor, theme, print, cookies, media_permissions, clear_cache))]
+const JS_ERROR_PAYLOAD: &str =
+  "{ category: 'webview', message: String(e && e.message || e), details: null }";
+
+/// Evaluates the given JS expression and resolves its value to the promise described by the `callback` and `error` function names.
+#[cfg(any(focus, scale_factor, theme, print))]
+fn eval_js_result<T: 'static>(
+  webview: &mut WebView<'_, T>,
+  expression: &str,
+  callback: String,
+  error: String,
+) -> crate::Result<()> {
+  webview.eval(&format!(
+    r#"try {{ window["{callback}"]({{ status: 'ok', data: {expression}, error: null }}) }} catch (e) {{ window["{error}"]({{ status: 'error', data: null, error: {payload} }}) }}"#,
+    callback = callback,
+    error = error,
+    expression = expression,
+    payload = JS_ERROR_PAYLOAD
+  ))?;
+  Ok(())
+}
+
+/// Evaluates the given JS expression, which can be a Promise,
+/// and resolves its value to the promise described by the `callback` and `error` function names.
+#[cfg(any(cookies, media_permissions, clear_cache))]
+fn eval_js_promise<T: 'static>(
+  webview: &mut WebView<'_, T>,
+  expression: &str,
+  callback: String,
+  error: String,
+) -> crate::Result<()> {
+  webview.eval(&format!(
+    r#"Promise.resolve().then(function () {{ return {expression} }}).then(function (data) {{ window["{callback}"]({{ status: 'ok', data: data, error: null }}) }}, function (e) {{ window["{error}"]({{ status: 'error', data: null, error: {payload} }}) }})"#,
+    callback = callback,
+    error = error,
+    expression = expression,
+    payload = JS_ERROR_PAYLOAD
+  ))?;
+  Ok(())
+}
+
+/// Rejects the promise described by the `error` function name
+/// stating that the API isn't supported by the webview backend on this platform.
+#[cfg(print)]
+fn unsupported<T: 'static>(
+  webview: &mut WebView<'_, T>,
+  api: &str,
+  callback: String,
+  error: String,
+) -> crate::Result<()> {
+  let message = format!("'{}' is not supported on this platform", api);
+  crate::execute_promise_sync(
+    webview,
+    move || -> crate::Result<()> { Err(anyhow::anyhow!(message)) },
+    callback,
+    error,
+  )
+}
+
+/// Warns on the webview console that the API isn't supported by the webview backend on this platform.
+#[cfg(any(
+  all(activation_policy, not(target_os = "macos")),
+  all(set_badge, not(target_os = "linux")),
+  all(visible_on_all_workspaces, not(target_os = "macos"))
+))]
+fn warn_unsupported<T: 'static>(webview: &mut WebView<'_, T>, api: &str) -> crate::Result<()> {
+  webview.eval(&format!(
+    r#"console.warn("'{}' is not supported on this platform")"#,
+    api
+  ))?;
+  Ok(())
+}
+
+/// Opens a link clicked in the window on the OS default browser,
+/// if the `tauri.window.externalLinks` policy allows it.
+#[cfg(open)]
+pub fn open_external_link(uri: String) -> crate::Result<()> {
+  if get()?.tauri.window.external_links != ExternalLinks::Browser {
+    return Err(anyhow::anyhow!(
+      "opening links on the browser is not allowed by the externalLinks policy"
+    ));
+  }
+  let scheme = uri.split(':').next().unwrap_or("");
+  if scheme != "http" && scheme != "https" && scheme != "mailto" {
+    return Err(anyhow::anyhow!("invalid external link {}", uri));
+  }
+  crate::spawn(move || {
+    if let Err(e) = webbrowser::open(&uri) {
+      eprintln!("failed to open {} on the browser: {}", uri, e);
+    }
+  });
+  Ok(())
+}
+
+/// Resolves the window focus state.
+#[cfg(focus)]
+pub fn is_focused<T: 'static>(
+  webview: &mut WebView<'_, T>,
+  callback: String,
+  error: String,
+) -> crate::Result<()> {
+  eval_js_result(webview, "document.hasFocus()", callback, error)
+}
+
+/// Replaces the headers added to the `fetch` and `XMLHttpRequest` requests to the content origin,
+/// e.g. with a refreshed authentication token.
+///
+/// The headers are kept until the content is reloaded, which restores the `build.requestHeaders` config.
+#[cfg(request_headers)]
+pub fn set_request_headers<T: 'static>(
+  webview: &mut WebView<'_, T>,
+  headers: HashMap<String, String>,
+) -> crate::Result<()> {
+  webview.eval(&format!(
+    "window['{}'] = {}",
+    super::init::REQUEST_HEADERS_NAME,
+    serde_json::to_string(&headers)?
+  ))?;
+  Ok(())
+}
+
+/// Sets the macOS activation policy, e.g. `Accessory` for a menu bar utility without a dock icon,
+/// switching back to `Regular` when a window is shown.
+/// The other platforms have no dock, so it only warns that the policy isn't supported.
+#[cfg(activation_policy)]
+pub fn set_activation_policy<T: 'static>(
+  webview: &mut WebView<'_, T>,
+  policy: ActivationPolicy,
+) -> crate::Result<()> {
+  #[cfg(target_os = "macos")]
+  {
+    use objc::runtime::{Object, BOOL, NO};
+    use objc::{class, msg_send, sel, sel_impl};
+    let _ = webview;
+    // the commands are handled on the main thread, where AppKit must be called
+    let applied: BOOL = unsafe {
+      let app: *mut Object = msg_send![class!(NSApplication), sharedApplication];
+      msg_send![app, setActivationPolicy: policy as i64]
+    };
+    if applied == NO {
+      return Err(anyhow::anyhow!("failed to set the activation policy"));
+    }
+    Ok(())
+  }
+  #[cfg(not(target_os = "macos"))]
+  {
+    let _ = policy;
+    warn_unsupported(webview, "setActivationPolicy")
+  }
+}
+
+/// Turns the confirmation asked before unloading the content on or off,
+/// e.g. off when the unsaved changes are saved.
+///
+/// The webview backend doesn't notify the app when the native window is closed,
+/// so the confirmation is only asked by the webview when the content is unloaded, e.g. on reloads.
+#[cfg(close_confirmation)]
+pub fn set_close_confirmation<T: 'static>(
+  webview: &mut WebView<'_, T>,
+  enabled: bool,
+) -> crate::Result<()> {
+  if !get()?.tauri.window.confirm_on_close {
+    return Err(anyhow::anyhow!(
+      "the close confirmation needs the `tauri.window.confirmOnClose` config"
+    ));
+  }
+  webview.eval(&format!(
+    "window['{}'] = {}",
+    super::init::CLOSE_CONFIRMATION_FLAG_NAME,
+    enabled
+  ))?;
+  Ok(())
+}
+
+/// Resolves the window scale factor, i.e. the physical pixels per CSS pixel.
+#[cfg(scale_factor)]
+pub fn scale_factor<T: 'static>(
+  webview: &mut WebView<'_, T>,
+  callback: String,
+  error: String,
+) -> crate::Result<()> {
+  eval_js_result(webview, "window.devicePixelRatio", callback, error)
+}
+
+/// Resolves the window theme: the `tauri.window.theme` config if it's set, or the OS theme.
+#[cfg(theme)]
+pub fn get_theme<T: 'static>(
+  webview: &mut WebView<'_, T>,
+  callback: String,
+  error: String,
+) -> crate::Result<()> {
+  match get()?.tauri.window.theme {
+    Some(theme) => crate::execute_promise_sync(webview, move || Ok(theme), callback, error),
+    None => eval_js_result(
+      webview,
+      "window.matchMedia('(prefers-color-scheme: dark)').matches ? 'dark' : 'light'",
+      callback,
+      error,
+    ),
+  }
+}
+
+/// The time after which a loading overlay is hidden if the timeout isn't set, in milliseconds.
+#[cfg(set_loading)]
+const DEFAULT_LOADING_TIMEOUT_MS: u64 = 30000;
+
+/// Shows or hides the loading overlay with the given message.
+/// The overlay is hidden after the timeout, so a stuck overlay doesn't lock the app.
+#[cfg(set_loading)]
+pub fn set_loading<T: 'static>(
+  webview: &mut WebView<'_, T>,
+  visible: bool,
+  message: Option<String>,
+  timeout: Option<u64>,
+) -> crate::Result<()> {
+  webview.eval(&format!(
+    "window['{name}']({visible}, {message}, {timeout})",
+    name = super::init::LOADING_OVERLAY_FUNCTION_NAME,
+    visible = visible,
+    message = serde_json::to_string(&message)?,
+    timeout = timeout.unwrap_or(DEFAULT_LOADING_TIMEOUT_MS)
+  ))?;
+  Ok(())
+}
+
+/// Sets the badge count shown on the app launcher entry; `None` or `0` clears it.
+///
+/// On Linux the count is sent with the Unity launcher protocol,
+/// implemented by docks like Plank or Dash to Dock.
+/// The webview backend doesn't expose the macOS dock and the Windows taskbar overlay APIs,
+/// so this only warns that the badge isn't supported there.
+#[cfg(set_badge)]
+pub fn set_badge<T: 'static>(
+  webview: &mut WebView<'_, T>,
+  count: Option<u64>,
+) -> crate::Result<()> {
+  #[cfg(target_os = "linux")]
+  {
+    let _ = webview;
+    let count = count.unwrap_or(0);
+    let exe = std::env::current_exe()?;
+    // the error is reported to the webview by the command handler, instead of panicking the app
+    let exe_name = exe.file_name().ok_or_else(|| {
+      anyhow::anyhow!(
+        "failed to get the launcher entry name from the executable path {}",
+        exe.display()
+      )
+    })?;
+    let app_uri = format!("application://{}.desktop", exe_name.to_string_lossy());
+    crate::spawn(move || {
+      let status = std::process::Command::new("gdbus")
+        .args(&[
+          "emit",
+          "--session",
+          "--object-path",
+          "/",
+          "--signal",
+          "com.canonical.Unity.LauncherEntry.Update",
+          &app_uri,
+          &format!(
+            "{{'count': <int64 {}>, 'count-visible': <{}>}}",
+            count,
+            count > 0
+          ),
+        ])
+        .status();
+      if !status.map(|status| status.success()).unwrap_or(false) {
+        eprintln!("failed to set the launcher badge; is a Unity launcher compatible dock running?");
+      }
+    });
+    Ok(())
+  }
+  #[cfg(not(target_os = "linux"))]
+  {
+    let _ = count;
+    warn_unsupported(webview, "setBadge")
+  }
+}
+
+/// Sets whether the window is shown on all the workspaces, i.e. the macOS spaces,
+/// e.g. for an overlay that follows the user across the desktops.
+///
+/// The behavior is kept by the native window, so it still applies when the window is hidden and shown again.
+/// The webview backend doesn't expose the native window on Linux and Windows,
+/// so it only warns that the behavior isn't supported there.
+#[cfg(visible_on_all_workspaces)]
+pub fn set_visible_on_all_workspaces<T: 'static>(
+  webview: &mut WebView<'_, T>,
+  value: bool,
+) -> crate::Result<()> {
+  #[cfg(target_os = "macos")]
+  {
+    use objc::runtime::Object;
+    use objc::{class, msg_send, sel, sel_impl};
+    /// The `NSWindowCollectionBehaviorCanJoinAllSpaces` flag.
+    const CAN_JOIN_ALL_SPACES: usize = 1;
+    let _ = webview;
+    // the commands are handled on the main thread, where AppKit must be called
+    unsafe {
+      let app: *mut Object = msg_send![class!(NSApplication), sharedApplication];
+      let windows: *mut Object = msg_send![app, windows];
+      let count: usize = msg_send![windows, count];
+      for index in 0..count {
+        let window: *mut Object = msg_send![windows, objectAtIndex: index];
+        let behavior: usize = msg_send![window, collectionBehavior];
+        let behavior = if value {
+          behavior | CAN_JOIN_ALL_SPACES
+        } else {
+          behavior & !CAN_JOIN_ALL_SPACES
+        };
+        let _: () = msg_send![window, setCollectionBehavior: behavior];
+      }
+    }
+    Ok(())
+  }
+  #[cfg(not(target_os = "macos"))]
+  {
+    let _ = value;
+    warn_unsupported(webview, "setVisibleOnAllWorkspaces")
+  }
+}
+
+/// Opens the native print dialog for the current page.
+///
+/// The webview backend can only print through the page, so silent printing
+/// to the default printer is rejected with an unsupported error.
+#[cfg(print)]
+pub fn print<T: 'static>(
+  webview: &mut WebView<'_, T>,
+  silent: bool,
+  callback: String,
+  error: String,
+) -> crate::Result<()> {
+  if silent {
+    return unsupported(webview, "silent print", callback, error);
+  }
+  eval_js_result(webview, "window.print()", callback, error)
+}
+
+/// Returns the JS that fails unless the given URL has the page origin.
+///
+/// The webview backends don't expose their cookie store, so the cookies are read and written
+/// with `document.cookie`, which only reaches the cookies of the page origin.
+#[cfg(cookies)]
+fn cookie_origin_check(url: &Option<String>) -> crate::Result<String> {
+  Ok(format!(
+    r#"(function (url) {{
+      if (url) {{
+        var a = document.createElement('a')
+        a.href = url
+        if (a.protocol + '//' + a.host !== window.location.protocol + '//' + window.location.host) {{
+          throw new Error('only the cookies of the app origin can be accessed')
+        }}
+      }}
+    }})({url});"#,
+    url = serde_json::to_string(url)?
+  ))
+}
+
+/// Resolves the `{ name, value }` cookies of the page origin.
+/// HttpOnly cookies aren't visible to the page, so they're not included.
+#[cfg(cookies)]
+pub fn get_cookies<T: 'static>(
+  webview: &mut WebView<'_, T>,
+  url: Option<String>,
+  callback: String,
+  error: String,
+) -> crate::Result<()> {
+  let expression = format!(
+    r#"(function () {{
+      {origin_check}
+      return document.cookie.split(';').filter(function (cookie) {{
+        return cookie.trim() !== ''
+      }}).map(function (cookie) {{
+        var index = cookie.indexOf('=')
+        return {{
+          name: decodeURIComponent(cookie.slice(0, index).trim()),
+          value: decodeURIComponent(cookie.slice(index + 1).trim())
+        }}
+      }})
+    }})()"#,
+    origin_check = cookie_origin_check(&url)?
+  );
+  eval_js_promise(webview, &expression, callback, error)
+}
+
+/// Sets a cookie on the page origin.
+#[cfg(cookies)]
+pub fn set_cookie<T: 'static>(
+  webview: &mut WebView<'_, T>,
+  cookie: Cookie,
+  url: Option<String>,
+  callback: String,
+  error: String,
+) -> crate::Result<()> {
+  let expression = format!(
+    r#"(function (cookie) {{
+      {origin_check}
+      var value = encodeURIComponent(cookie.name) + '=' + encodeURIComponent(cookie.value)
+      value += '; path=' + (cookie.path || '/')
+      if (cookie.maxAge !== null && cookie.maxAge !== undefined) {{
+        value += '; max-age=' + cookie.maxAge
+      }}
+      if (cookie.secure) {{
+        value += '; secure'
+      }}
+      if (cookie.sameSite) {{
+        value += '; samesite=' + cookie.sameSite
+      }}
+      document.cookie = value
+      return null
+    }})({cookie})"#,
+    origin_check = cookie_origin_check(&url)?,
+    cookie = serde_json::to_string(&cookie)?
+  );
+  eval_js_promise(webview, &expression, callback, error)
+}
+
+/// Deletes a cookie of the page origin by expiring it.
+#[cfg(cookies)]
+pub fn delete_cookie<T: 'static>(
+  webview: &mut WebView<'_, T>,
+  name: String,
+  path: Option<String>,
+  url: Option<String>,
+  callback: String,
+  error: String,
+) -> crate::Result<()> {
+  let expression = format!(
+    r#"(function (name, path) {{
+      {origin_check}
+      document.cookie = encodeURIComponent(name) + '=; path=' + (path || '/') + '; expires=Thu, 01 Jan 1970 00:00:00 GMT'
+      return null
+    }})({name}, {path})"#,
+    origin_check = cookie_origin_check(&url)?,
+    name = serde_json::to_string(&name)?,
+    path = serde_json::to_string(&path)?
+  );
+  eval_js_promise(webview, &expression, callback, error)
+}
+
+/// Rejects the media permissions APIs if `tauri.security.mediaAccess` isn't enabled.
+#[cfg(media_permissions)]
+fn media_access_check() -> crate::Result<()> {
+  if get()?.tauri.security.media_access {
+    Ok(())
+  } else {
+    Err(anyhow::anyhow!(
+      "the media permissions API needs the `tauri.security.mediaAccess` config"
+    ))
+  }
+}
+
+/// Resolves the camera and microphone permission states:
+/// `granted`, `denied`, `prompt`, or `unknown` if the webview can't query it.
+#[cfg(media_permissions)]
+pub fn get_media_permissions<T: 'static>(
+  webview: &mut WebView<'_, T>,
+  callback: String,
+  error: String,
+) -> crate::Result<()> {
+  if let Err(e) = media_access_check() {
+    return crate::execute_promise_sync(
+      webview,
+      move || -> crate::Result<()> { Err(e) },
+      callback,
+      error,
+    );
+  }
+  eval_js_promise(webview, MEDIA_PERMISSIONS_JS, callback, error)
+}
+
+/// Requests the camera and/or microphone access, showing the platform prompt if the user
+/// hasn't answered it yet, and resolves the permission states afterwards.
+///
+/// The access is requested with `getUserMedia`; the captured tracks are stopped right away.
+#[cfg(media_permissions)]
+pub fn request_media_permissions<T: 'static>(
+  webview: &mut WebView<'_, T>,
+  camera: bool,
+  microphone: bool,
+  callback: String,
+  error: String,
+) -> crate::Result<()> {
+  if let Err(e) = media_access_check() {
+    return crate::execute_promise_sync(
+      webview,
+      move || -> crate::Result<()> { Err(e) },
+      callback,
+      error,
+    );
+  }
+  let expression = format!(
+    r#"(function (constraints) {{
+      if (!constraints.video && !constraints.audio) {{
+        return {query}
+      }}
+      if (!navigator.mediaDevices || !navigator.mediaDevices.getUserMedia) {{
+        throw new Error('the webview does not support the media devices API')
+      }}
+      return navigator.mediaDevices.getUserMedia(constraints).then(function (stream) {{
+        stream.getTracks().forEach(function (track) {{
+          track.stop()
+        }})
+      }}, function (e) {{
+        if (e.name !== 'NotAllowedError') {{
+          throw e
+        }}
+      }}).then(function () {{
+        return {query}
+      }})
+    }})({{ video: {camera}, audio: {microphone} }})"#,
+    query = MEDIA_PERMISSIONS_JS,
+    camera = camera,
+    microphone = microphone
+  );
+  eval_js_promise(webview, &expression, callback, error)
+}
+
+/// The JS expression resolving the camera and microphone permission states.
+#[cfg(media_permissions)]
+const MEDIA_PERMISSIONS_JS: &str = r#"(function () {
+  function query(name) {
+    if (!navigator.permissions || !navigator.permissions.query) {
+      return Promise.resolve('unknown')
+    }
+    return navigator.permissions.query({ name: name }).then(function (status) {
+      return status.state
+    }, function () {
+      return 'unknown'
+    })
+  }
+  return Promise.all([query('camera'), query('microphone')]).then(function (states) {
+    return { camera: states[0], microphone: states[1] }
+  })
+})()"#;
+
+/// Clears the given webview storage kinds, resolving the kinds that were actually cleared.
+///
+/// The storage is cleared with the web APIs available to the page, so `cache` only clears the Cache Storage
+/// and `cookies` is only reported as cleared if no cookie is left (HttpOnly cookies can't be removed).
+#[cfg(clear_cache)]
+pub fn clear_cache<T: 'static>(
+  webview: &mut WebView<'_, T>,
+  kinds: Vec<StorageKind>,
+  callback: String,
+  error: String,
+) -> crate::Result<()> {
+  let expression = format!(
+    r#"(function (kinds) {{
+      var cleared = []
+      var pending = []
+      kinds.forEach(function (kind) {{
+        if (kind === 'localStorage' && window.localStorage) {{
+          window.localStorage.clear()
+          cleared.push(kind)
+        }} else if (kind === 'sessionStorage' && window.sessionStorage) {{
+          window.sessionStorage.clear()
+          cleared.push(kind)
+        }} else if (kind === 'cookies') {{
+          document.cookie.split(';').forEach(function (cookie) {{
+            var name = cookie.split('=')[0].trim()
+            if (name) {{
+              document.cookie = name + '=; expires=Thu, 01 Jan 1970 00:00:00 GMT; path=/'
+            }}
+          }})
+          if (document.cookie === '') {{
+            cleared.push(kind)
+          }}
+        }} else if (kind === 'cache' && window.caches) {{
+          pending.push(window.caches.keys().then(function (keys) {{
+            return Promise.all(keys.map(function (key) {{ return window.caches.delete(key) }}))
+          }}).then(function () {{
+            cleared.push(kind)
+          }}))
+        }}
+      }})
+      return Promise.all(pending).then(function () {{ return cleared }})
+    }})({kinds})"#,
+    kinds = serde_json::to_string(&kinds)?
+  );
+  eval_js_promise(webview, &expression, callback, error)
+}
@@ -0,0 +1,174 @@
+use serde::Serialize;
+use thiserror::Error;
+
+/// The category of an error, sent to the frontend so it can branch on it.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ErrorCategory {
+  /// The `tauri.conf.json` is invalid.
+  Config,
+  /// A file system or IO operation failed.
+  Io,
+  /// The embedded server or the dev server failed.
+  Server,
+  /// The webview failed.
+  Webview,
+  /// A command failed or couldn't be matched.
+  Command,
+  /// The updater failed.
+  Updater,
+}
+
+/// The error types.
+#[derive(Error, Debug)]
+pub enum Error {
+  /// The config error.
+  #[error("Config Error: {0}")]
+  Config(String),
+  /// The IO error.
+  #[error("IO Error: {0}")]
+  Io(String),
+  /// The embedded server or dev server error.
+  #[error("Server Error: {0}")]
+  Server(String),
+  /// The webview error.
+  #[error("Webview Error: {0}")]
+  Webview(String),
+  /// The command error.
+  #[error("Command Error: {0}")]
+  Command(String),
+  /// The updater error.
+  #[error("Updater Error: {0}")]
+  Updater(String),
+}
+
+impl Error {
+  /// The error category.
+  pub fn category(&self) -> ErrorCategory {
+    match self {
+      Self::Config(_) => ErrorCategory::Config,
+      Self::Io(_) => ErrorCategory::Io,
+      Self::Server(_) => ErrorCategory::Server,
+      Self::Webview(_) => ErrorCategory::Webview,
+      Self::Command(_) => ErrorCategory::Command,
+      Self::Updater(_) => ErrorCategory::Updater,
+    }
+  }
+
+  /// The error message, without the category prefix.
+  pub fn message(&self) -> &str {
+    match self {
+      Self::Config(message)
+      | Self::Io(message)
+      | Self::Server(message)
+      | Self::Webview(message)
+      | Self::Command(message)
+      | Self::Updater(message) => message,
+    }
+  }
+}
+
+/// The error object sent to the frontend.
+#[derive(Debug, PartialEq, Serialize)]
+pub(crate) struct ErrorPayload {
+  /// The error category.
+  pub(crate) category: ErrorCategory,
+  /// The error message.
+  pub(crate) message: String,
+  /// The causes of the error, if any.
+  pub(crate) details: Option<String>,
+}
+
+impl ErrorPayload {
+  /// Creates a payload without details.
+  pub(crate) fn new(category: ErrorCategory, message: impl Into<String>) -> Self {
+    Self {
+      category,
+      message: message.into(),
+      details: None,
+    }
+  }
+}
+
+impl From<ErrorPayload> for serde_json::Value {
+  fn from(payload: ErrorPayload) -> Self {
+    serde_json::to_value(payload).expect("failed to serialize the error payload")
+  }
+}
+
+impl From<&anyhow::Error> for ErrorPayload {
+  fn from(error: &anyhow::Error) -> Self {
+    let (category, message) = if let Some(e) = error.downcast_ref::<Error>() {
+      (e.category(), e.message().to_string())
+    } else if let Some(e) = error.downcast_ref::<tauri_api::Error>() {
+      let category = match e {
+        tauri_api::Error::Extract(_) => ErrorCategory::Updater,
+        tauri_api::Error::Command(_) => ErrorCategory::Command,
+        tauri_api::Error::File(_) | tauri_api::Error::Path(_) | tauri_api::Error::Permission(_) => {
+          ErrorCategory::Io
+        }
+        tauri_api::Error::Dialog(_) => ErrorCategory::Webview,
+        tauri_api::Error::Network(_) | tauri_api::Error::ResponseSize(_) => ErrorCategory::Server,
+        tauri_api::Error::Proxy(_) => ErrorCategory::Config,
+      };
+      (category, e.to_string())
+    } else if error.downcast_ref::<std::io::Error>().is_some() {
+      (ErrorCategory::Io, error.to_string())
+    } else if error.downcast_ref::<web_view::Error>().is_some() {
+      (ErrorCategory::Webview, error.to_string())
+    } else {
+      (ErrorCategory::Command, error.to_string())
+    };
+    let causes: Vec<String> = error.chain().skip(1).map(|e| e.to_string()).collect();
+    Self {
+      category,
+      message,
+      details: if causes.is_empty() {
+        None
+      } else {
+        Some(causes.join(": "))
+      },
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::{Error, ErrorCategory, ErrorPayload};
+
+  #[test]
+  // check that the errors are mapped to their category
+  fn check_error_payload() {
+    let error = anyhow::Error::from(Error::Server("the dev server did not respond".to_string()));
+    assert_eq!(
+      ErrorPayload::from(&error),
+      ErrorPayload::new(ErrorCategory::Server, "the dev server did not respond")
+    );
+
+    let error = anyhow::Error::from(std::io::Error::new(
+      std::io::ErrorKind::NotFound,
+      "file not found",
+    ));
+    assert_eq!(ErrorPayload::from(&error).category, ErrorCategory::Io);
+
+    let error = anyhow::Error::from(tauri_api::Error::Proxy("invalid proxy url".to_string()));
+    let payload = ErrorPayload::from(&error);
+    assert_eq!(payload.category, ErrorCategory::Config);
+    assert!(payload.message.contains("invalid proxy url"));
+
+    let error = anyhow::anyhow!("unknown variant `foo`").context("failed to parse the command");
+    let payload = ErrorPayload::from(&error);
+    assert_eq!(payload.category, ErrorCategory::Command);
+    assert_eq!(payload.details, Some("unknown variant `foo`".to_string()));
+  }
+
+  #[test]
+  // check the serialized error object
+  fn check_error_payload_json() {
+    let payload = ErrorPayload::new(ErrorCategory::Config, "invalid port");
+    assert_eq!(
+      serde_json::to_string(&payload).unwrap(),
+      r#"{"category":"config","message":"invalid port","details":null}"#
+    );
+  }
+}
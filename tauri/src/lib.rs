@@ -16,11 +16,15 @@
 pub mod assets;
 /// The event system module.
 pub mod event;
+/// The rotating log file for your app diagnostics.
+pub mod logger;
 /// The embedded server helpers.
 #[cfg(embedded_server)]
 pub mod server;
 /// The Tauri-specific settings for your app e.g. notification permission status.
 pub mod settings;
+/// The persisted key-value store for your app settings.
+pub mod store;
 
 /// The CLI args interface.
 #[cfg(cli)]
@@ -28,20 +32,32 @@ pub mod cli;
 
 /// The webview application entry.
 mod app;
+/// The crash report writer.
+mod crash_reporter;
 /// The Tauri API endpoints.
 mod endpoints;
+/// The Tauri error types.
+mod error;
 /// The salt helpers.
 mod salt;
+/// The startup timing metrics.
+#[cfg(startup_metrics)]
+mod startup;
+/// The updater install helpers.
+#[cfg(feature = "updater")]
+mod updater;
 
 /// Alias for a Result with error type anyhow::Error.
 pub use anyhow::Result;
 pub use app::*;
+pub use error::{Error, ErrorCategory};
 pub use tauri_api as api;
 pub use web_view::Handle;
 
 use std::process::Stdio;
 
-use api::rpc::{format_callback, format_callback_result};
+use api::rpc::{format_callback_error, format_callback_result};
+use error::ErrorPayload;
 use serde::Serialize;
 use threadpool::ThreadPool;
 use web_view::WebView;
@@ -70,8 +86,7 @@ pub fn execute_promise_sync<
   error: String,
 ) -> crate::Result<()> {
   let handle = webview.handle();
-  let callback_string =
-    format_callback_result(task().map_err(|err| err.to_string()), callback, error)?;
+  let callback_string = format_task_result(task(), callback, error);
   handle.dispatch(move |_webview| _webview.eval(callback_string.as_str()))?;
   Ok(())
 }
@@ -94,14 +109,7 @@ pub fn execute_promise<
   let handle = webview.handle();
   POOL.with(|thread| {
     thread.execute(move || {
-      let callback_string = match format_callback_result(
-        task().map_err(|err| err.to_string()),
-        success_callback,
-        error_callback.clone(),
-      ) {
-        Ok(callback_string) => callback_string,
-        Err(e) => format_callback(error_callback, e.to_string()),
-      };
+      let callback_string = format_task_result(task(), success_callback, error_callback);
       handle
         .dispatch(move |_webview| _webview.eval(callback_string.as_str()))
         .expect("Failed to dispatch promise callback")
@@ -109,6 +117,23 @@ pub fn execute_promise<
   });
 }
 
+/// Formats the result of a task to the JS promise described by the `callback` and `error` function names,
+/// rejecting it with the `{ category, message, details }` error object if the task failed.
+fn format_task_result<R: Serialize>(
+  result: crate::Result<R>,
+  callback: String,
+  error: String,
+) -> String {
+  match format_callback_result(
+    result.map_err(|err| ErrorPayload::from(&err)),
+    callback,
+    error.clone(),
+  ) {
+    Ok(callback_string) => callback_string,
+    Err(e) => format_callback_error(error, ErrorPayload::from(&e)),
+  }
+}
+
 /// Calls the given command and evaluates its output to the JS promise described by the `callback` and `error` function names.
 pub fn call<T: 'static>(
   webview: &mut WebView<'_, T>,
@@ -138,6 +163,21 @@ pub fn close_splashscreen<T: 'static>(webview_handle: &Handle<T>) -> crate::Resu
 mod test {
   use proptest::prelude::*;
 
+  #[test]
+  // check that the failed tasks reject with the same error object as the failed commands
+  fn check_format_task_result() {
+    let error = anyhow::Error::from(crate::Error::Io("file not found".to_string()));
+    let payload = crate::error::ErrorPayload::from(&error);
+    assert_eq!(
+      super::format_task_result::<()>(Err(error), "cb".to_string(), "err".to_string()),
+      super::format_callback_error("err", payload)
+    );
+    assert_eq!(
+      super::format_task_result(Ok(5), "cb".to_string(), "err".to_string()),
+      r#"window["cb"]({"status":"ok","data":5,"error":null})"#
+    );
+  }
+
   proptest! {
     #![proptest_config(ProptestConfig::with_cases(10000))]
     #[test]
@@ -0,0 +1,123 @@
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use once_cell::sync::Lazy;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri_api::path::{resolve_path, BaseDirectory};
+
+/// The maximum size of a log file before it's rotated, in bytes.
+const MAX_FILE_SIZE: u64 = 1024 * 1024;
+/// The maximum number of log files kept, including the current one.
+/// The log files take at most `MAX_FILE_SIZE * MAX_FILES` bytes.
+const MAX_FILES: usize = 5;
+
+/// The current log file, lazily opened on the first write.
+static LOG_FILE: Lazy<Mutex<Option<File>>> = Lazy::new(|| Mutex::new(None));
+
+/// The logger instance registered with `init`.
+static LOGGER: Logger = Logger;
+
+/// A `log` implementation that writes the records to the rotating app log file.
+pub struct Logger;
+
+impl Log for Logger {
+  fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+    metadata.level() <= log::max_level()
+  }
+
+  fn log(&self, record: &Record<'_>) {
+    if self.enabled(record.metadata()) {
+      if let Err(e) = write(record.level(), record.target(), &record.args().to_string()) {
+        eprintln!("failed to write to the log file: {}", e);
+      }
+    }
+  }
+
+  fn flush(&self) {
+    if let Ok(mut file) = LOG_FILE.lock() {
+      if let Some(file) = file.as_mut() {
+        let _ = file.flush();
+      }
+    }
+  }
+}
+
+/// Registers the `Logger` as the `log` logger with the given max level,
+/// so the Rust and webview logs are written to the same file.
+///
+/// Fails if a logger was already registered.
+pub fn init(level: LevelFilter) -> crate::Result<()> {
+  log::set_logger(&LOGGER).map_err(|e| anyhow::anyhow!(e.to_string()))?;
+  log::set_max_level(level);
+  Ok(())
+}
+
+/// Gets the path to the current log file.
+pub fn log_path() -> crate::Result<PathBuf> {
+  resolve_path("logs/app.log", Some(BaseDirectory::App))
+}
+
+/// Gets the path to the rotated log file with the given index.
+fn rotated_log_path(index: usize) -> crate::Result<PathBuf> {
+  resolve_path(format!("logs/app.{}.log", index), Some(BaseDirectory::App))
+}
+
+/// Renames `app.log` to `app.1.log`, `app.1.log` to `app.2.log` and so on,
+/// deleting the oldest file.
+fn rotate() -> crate::Result<()> {
+  let oldest = rotated_log_path(MAX_FILES - 1)?;
+  if oldest.exists() {
+    fs::remove_file(oldest)?;
+  }
+  for index in (1..MAX_FILES - 1).rev() {
+    let path = rotated_log_path(index)?;
+    if path.exists() {
+      fs::rename(path, rotated_log_path(index + 1)?)?;
+    }
+  }
+  fs::rename(log_path()?, rotated_log_path(1)?)?;
+  Ok(())
+}
+
+/// Opens the current log file for appending.
+fn open_log_file() -> crate::Result<File> {
+  let path = log_path()?;
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent)?;
+  }
+  Ok(OpenOptions::new().create(true).append(true).open(path)?)
+}
+
+/// Appends a line to the log file, rotating it when it exceeds the maximum size.
+pub fn write(level: Level, target: &str, message: &str) -> crate::Result<()> {
+  let mut file = LOG_FILE.lock().expect("Failed to lock log file mutex");
+  if file.is_none() {
+    *file = Some(open_log_file()?);
+  }
+  if file
+    .as_ref()
+    .expect("failed to get log file")
+    .metadata()?
+    .len()
+    >= MAX_FILE_SIZE
+  {
+    *file = None;
+    rotate()?;
+    *file = Some(open_log_file()?);
+  }
+  let timestamp = SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|duration| duration.as_millis())
+    .unwrap_or_default();
+  writeln!(
+    file.as_mut().expect("failed to get log file"),
+    "{} [{}] {}: {}",
+    timestamp,
+    level,
+    target,
+    message.replace('\n', "\\n")
+  )?;
+  Ok(())
+}
@@ -1,38 +1,397 @@
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::io::{self, ErrorKind};
+use std::sync::Mutex;
+use std::time::Duration;
 use tiny_http::{Header, Response};
 
-/// Returns the HTTP response of the given asset path.
-pub fn asset_response(path: &str) -> Response<std::io::Cursor<Vec<u8>>> {
-  let asset_path = &format!(
+/// The assets loaded in memory by `prime_assets`, by path.
+static PRIMED_ASSETS: Lazy<Mutex<HashMap<String, Vec<u8>>>> =
+  Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// The pre-compressed sibling extensions, by `Content-Encoding`, in order of preference.
+const PRECOMPRESSED_EXTENSIONS: &[(&str, &str)] = &[("br", ".br"), ("gzip", ".gz")];
+
+/// How many times an asset read is retried after a transient error.
+const READ_RETRIES: u32 = 3;
+
+/// The delay before the first retry of an asset read, doubled on every retry.
+const READ_RETRY_BACKOFF: Duration = Duration::from_millis(10);
+
+/// Checks whether the given read error is transient, e.g. an interrupted read,
+/// or a file locked by an antivirus scan on Windows.
+fn is_transient(error: &io::Error) -> bool {
+  match error.kind() {
+    ErrorKind::Interrupted | ErrorKind::WouldBlock | ErrorKind::TimedOut => true,
+    // ERROR_SHARING_VIOLATION and ERROR_LOCK_VIOLATION
+    _ if cfg!(windows) => match error.raw_os_error() {
+      Some(32) | Some(33) => true,
+      _ => false,
+    },
+    _ => false,
+  }
+}
+
+/// Runs the given read, retrying it with a short backoff while it fails with a transient error.
+/// The embedded assets are read from memory, so they never hit the retries.
+fn read_with_retry<T, F: FnMut() -> io::Result<T>>(mut read: F) -> io::Result<T> {
+  let mut backoff = READ_RETRY_BACKOFF;
+  let mut retries = 0;
+  loop {
+    match read() {
+      Err(ref e) if retries < READ_RETRIES && is_transient(e) => {
+        std::thread::sleep(backoff);
+        backoff *= 2;
+        retries += 1;
+      }
+      result => return result,
+    }
+  }
+}
+
+/// Gets the path of the given asset in the embedded assets.
+fn embedded_asset_path(path: &str) -> String {
+  format!(
     "{}{}",
     option_env!("TAURI_DIST_DIR")
       .expect("tauri apps should be built with the TAURI_DIST_DIR environment variable"),
     path
-  );
-  let asset = crate::assets::ASSETS
-    .get(asset_path)
+  )
+}
+
+/// Reads the given asset path from the embedded assets.
+fn read_asset(path: &str) -> Vec<u8> {
+  let asset_path = &embedded_asset_path(path);
+  read_with_retry(|| crate::assets::ASSETS.get(asset_path))
     .unwrap_or_else(|_| panic!("Could not read asset {}", asset_path))
-    .into_owned();
-  let mut response = Response::from_data(asset);
-  let header;
+    .into_owned()
+}
 
+/// Reads the given asset path from the primed or the embedded assets, if it exists.
+fn find_asset(path: &str) -> Option<Vec<u8>> {
+  let primed = PRIMED_ASSETS
+    .lock()
+    .expect("Failed to lock primed assets")
+    .get(path)
+    .cloned();
+  primed.or_else(|| {
+    let asset_path = &embedded_asset_path(path);
+    read_with_retry(|| crate::assets::ASSETS.get(asset_path))
+      .ok()
+      .map(|asset| asset.into_owned())
+  })
+}
+
+/// Gets the given asset path from the primed assets, or loads it with `load`.
+fn cached_asset<F: FnOnce() -> Vec<u8>>(path: &str, load: F) -> Vec<u8> {
+  let primed = PRIMED_ASSETS
+    .lock()
+    .expect("Failed to lock primed assets")
+    .get(path)
+    .cloned();
+  primed.unwrap_or_else(load)
+}
+
+/// Loads the given asset paths in memory with `load`, skipping the ones already loaded.
+fn prime<F: Fn(&str) -> Option<Vec<u8>>>(paths: &[String], load: F) {
+  for path in paths {
+    let primed = PRIMED_ASSETS
+      .lock()
+      .expect("Failed to lock primed assets")
+      .contains_key(path);
+    if !primed {
+      if let Some(asset) = load(path) {
+        PRIMED_ASSETS
+          .lock()
+          .expect("Failed to lock primed assets")
+          .insert(path.clone(), asset);
+      }
+    }
+  }
+}
+
+/// Loads the entry `index.tauri.html` and the local assets it references in memory,
+/// so the first requests the webview makes are served without reading the disk.
+pub fn prime_assets() {
+  let load = |path: &str| {
+    let asset = find_asset(path);
+    if asset.is_none() {
+      eprintln!("failed to prime the asset {}", path);
+    }
+    asset
+  };
+  let entry = "/index.tauri.html".to_string();
+  prime(&[entry.clone()], load);
+  let html = cached_asset(&entry, Vec::new);
+  prime(&asset_dependencies(&String::from_utf8_lossy(&html)), load);
+}
+
+/// Loads the given asset paths in memory, e.g. the assets of the next view,
+/// so the requests for them are served without reading the disk.
+/// The paths can have a query or a fragment, and `/` is the entry `index.tauri.html`.
+///
+/// Returns the paths that couldn't be loaded.
+pub fn prefetch_assets(paths: &[String]) -> Vec<String> {
+  let normalized: Vec<String> = paths.iter().map(|path| normalize_path(path)).collect();
+  prime(&normalized, find_asset);
+  let primed = PRIMED_ASSETS.lock().expect("Failed to lock primed assets");
+  paths
+    .iter()
+    .zip(&normalized)
+    .filter(|(_, normalized)| !primed.contains_key(*normalized))
+    .map(|(path, _)| path.clone())
+    .collect()
+}
+
+/// Normalizes the given request path to the path of an asset, e.g. `/app.js?v=1` to `/app.js`.
+fn normalize_path(path: &str) -> String {
+  let path = path.split(|c| c == '?' || c == '#').next().unwrap_or("");
+  match path.trim_start_matches('/') {
+    "" => "/index.tauri.html".to_string(),
+    path => format!("/{}", path),
+  }
+}
+
+/// Gets the local asset paths referenced by the `src` and `href` attributes of the given HTML.
+fn asset_dependencies(html: &str) -> Vec<String> {
+  let mut paths = Vec::new();
+  for attribute in &["src=", "href="] {
+    for (index, _) in html.match_indices(attribute) {
+      let value = &html[index + attribute.len()..];
+      let quote = match value.chars().next() {
+        Some(quote) if quote == '"' || quote == '\'' => quote,
+        _ => continue,
+      };
+      let value = match value[1..].find(quote) {
+        Some(end) => &value[1..=end],
+        None => continue,
+      };
+      let path = value.split(|c| c == '?' || c == '#').next().unwrap_or("");
+      if path.is_empty() || path.starts_with("//") || path.contains(':') {
+        // skip the remote and the data, javascript and mailto URLs
+        continue;
+      }
+      let path = format!("/{}", path.trim_start_matches("./").trim_start_matches('/'));
+      if !paths.contains(&path) {
+        paths.push(path);
+      }
+    }
+  }
+  paths
+}
+
+/// Gets the encodings allowed by the given `Accept-Encoding` header value
+/// that have a pre-compressed sibling extension, in order of preference.
+fn accepted_encodings(accept_encoding: &str) -> Vec<(&'static str, &'static str)> {
+  let accepted: Vec<&str> = accept_encoding
+    .split(',')
+    .filter_map(|encoding| {
+      let mut parts = encoding.split(';');
+      let name = parts.next().unwrap_or("").trim();
+      let rejected = parts.any(|param| {
+        let param = param.trim();
+        param.starts_with("q=") && param[2..].trim().parse::<f32>().ok() == Some(0.0)
+      });
+      if name.is_empty() || rejected {
+        None
+      } else {
+        Some(name)
+      }
+    })
+    .collect();
+  PRECOMPRESSED_EXTENSIONS
+    .iter()
+    .filter(|(encoding, _)| {
+      accepted
+        .iter()
+        .any(|name| name.eq_ignore_ascii_case(encoding) || *name == "*")
+    })
+    .cloned()
+    .collect()
+}
+
+/// Gets the `Content-Type` of the given asset path from the `tauri.embeddedServer.mimeOverrides` config,
+/// whose extensions are lowercase.
+fn mime_override<'a>(path: &str, overrides: &'a HashMap<String, String>) -> Option<&'a str> {
+  std::path::Path::new(&normalize_path(path))
+    .extension()
+    .and_then(|extension| overrides.get(&extension.to_string_lossy().to_lowercase()))
+    .map(|mime| mime.as_str())
+}
+
+/// Gets the built-in `Content-Type` of the given asset path.
+fn content_type(path: &str) -> &'static str {
   if path.ends_with(".svg") {
-    header = Header::from_bytes(&b"Content-Type"[..], &b"image/svg+xml"[..])
-      .expect("Could not add svg+xml header");
+    "image/svg+xml"
   } else if path.ends_with(".css") {
-    header =
-      Header::from_bytes(&b"Content-Type"[..], &b"text/css"[..]).expect("Could not add css header");
+    "text/css"
   } else if path.ends_with(".html") {
-    header = Header::from_bytes(&b"Content-Type"[..], &b"text/html"[..])
-      .expect("Could not add html header");
+    "text/html"
   } else if path.ends_with(".js") {
-    header = Header::from_bytes(&b"Content-Type"[..], &b"text/javascript"[..])
-      .expect("Could not add Javascript header");
+    "text/javascript"
   } else {
-    header = Header::from_bytes(&b"Content-Type"[..], &b"application/octet-stream"[..])
-      .expect("Could not add octet-stream header");
+    "application/octet-stream"
   }
+}
 
-  response.add_header(header);
+/// Returns the HTTP response of the given asset path.
+///
+/// If the request `Accept-Encoding` allows it and the assets have a pre-compressed
+/// `.br` or `.gz` sibling of the path, the sibling is served with the `Content-Encoding` header.
+pub fn asset_response(
+  path: &str,
+  accept_encoding: Option<&str>,
+) -> Response<std::io::Cursor<Vec<u8>>> {
+  let precompressed = accept_encoding
+    .map(accepted_encodings)
+    .unwrap_or_default()
+    .into_iter()
+    .find_map(|(encoding, extension)| {
+      find_asset(&format!("{}{}", path, extension)).map(|asset| (encoding, asset))
+    });
+  let mut response = match precompressed {
+    Some((encoding, asset)) => {
+      let mut response = Response::from_data(asset);
+      response.add_header(
+        Header::from_bytes(&b"Content-Encoding"[..], encoding.as_bytes())
+          .expect("Could not add Content-Encoding header"),
+      );
+      response.add_header(
+        Header::from_bytes(&b"Vary"[..], &b"Accept-Encoding"[..])
+          .expect("Could not add Vary header"),
+      );
+      response
+    }
+    None => Response::from_data(cached_asset(path, || read_asset(path))),
+  };
+
+  let content_type = tauri_api::config::get()
+    .ok()
+    .and_then(|config| mime_override(path, &config.tauri.embedded_server.mime_overrides))
+    .unwrap_or_else(|| content_type(path));
+  response.add_header(
+    Header::from_bytes(&b"Content-Type"[..], content_type.as_bytes())
+      .expect("Could not add Content-Type header"),
+  );
 
   response
 }
+
+#[cfg(test)]
+mod test {
+  use std::io::{self, ErrorKind};
+  use std::time::{Duration, Instant};
+
+  #[test]
+  // check that the transient read errors are retried and the other errors are returned right away
+  fn check_read_with_retry() {
+    let mut attempts = 0;
+    let asset = super::read_with_retry(|| {
+      attempts += 1;
+      if attempts < 3 {
+        Err(io::Error::from(ErrorKind::Interrupted))
+      } else {
+        Ok(b"asset".to_vec())
+      }
+    });
+    assert_eq!(asset.unwrap(), b"asset".to_vec());
+    assert_eq!(attempts, 3);
+
+    let mut attempts = 0;
+    let asset: io::Result<Vec<u8>> = super::read_with_retry(|| {
+      attempts += 1;
+      Err(io::Error::from(ErrorKind::Interrupted))
+    });
+    assert_eq!(asset.unwrap_err().kind(), ErrorKind::Interrupted);
+    assert_eq!(attempts, super::READ_RETRIES + 1);
+
+    let mut attempts = 0;
+    let asset: io::Result<Vec<u8>> = super::read_with_retry(|| {
+      attempts += 1;
+      Err(io::Error::from(ErrorKind::NotFound))
+    });
+    assert_eq!(asset.unwrap_err().kind(), ErrorKind::NotFound);
+    assert_eq!(attempts, 1);
+  }
+
+  #[test]
+  // check that the request paths are normalized to the asset paths
+  fn check_normalize_path() {
+    assert_eq!(super::normalize_path("/"), "/index.tauri.html");
+    assert_eq!(super::normalize_path("/js/app.js?v=1#top"), "/js/app.js");
+    assert_eq!(super::normalize_path("app.css"), "/app.css");
+  }
+
+  #[test]
+  // check that the MIME overrides are matched by the lowercase extension
+  fn check_mime_override() {
+    let mut overrides = std::collections::HashMap::new();
+    overrides.insert("glb".to_string(), "model/gltf-binary".to_string());
+    assert_eq!(
+      super::mime_override("/models/Scene.GLB?v=2", &overrides),
+      Some("model/gltf-binary")
+    );
+    assert_eq!(super::mime_override("/app.js", &overrides), None);
+    assert_eq!(super::mime_override("/LICENSE", &overrides), None);
+  }
+
+  #[test]
+  // check that the local assets referenced by the HTML are found
+  fn check_asset_dependencies() {
+    let html = r##"<html><head>
+      <link rel="stylesheet" href="/app.css?v=1">
+      <link rel="icon" href='./favicon.svg'>
+      <link rel="preconnect" href="https://fonts.example.com">
+      <script src="js/app.js"></script>
+      <script src="//cdn.example.com/lib.js"></script>
+      <img src="data:image/png;base64,AAAA">
+      <a href="#top">top</a>
+      <script src="/app.css"></script>
+    </head></html>"##;
+    assert_eq!(
+      super::asset_dependencies(html),
+      vec!["/js/app.js", "/app.css", "/favicon.svg"]
+    );
+  }
+
+  #[test]
+  // check that the pre-compressed encodings are picked from the Accept-Encoding header
+  fn check_accepted_encodings() {
+    assert_eq!(
+      super::accepted_encodings("gzip, deflate, br"),
+      vec![("br", ".br"), ("gzip", ".gz")]
+    );
+    assert_eq!(
+      super::accepted_encodings("br;q=0, gzip;q=0.8"),
+      vec![("gzip", ".gz")]
+    );
+    assert_eq!(
+      super::accepted_encodings("*"),
+      vec![("br", ".br"), ("gzip", ".gz")]
+    );
+    assert_eq!(super::accepted_encodings("identity"), vec![]);
+    assert_eq!(super::accepted_encodings(""), vec![]);
+  }
+
+  #[test]
+  // check that the primed assets are served from memory, measuring the first load saved by priming
+  fn check_primed_assets() {
+    let slow_load = |_path: &str| {
+      std::thread::sleep(Duration::from_millis(50));
+      b"primed".to_vec()
+    };
+    let paths = vec!["/check-primed.js".to_string()];
+
+    let start = Instant::now();
+    super::prime(&paths, |path| Some(slow_load(path)));
+    let prime_time = start.elapsed();
+
+    let start = Instant::now();
+    let asset = super::cached_asset("/check-primed.js", || slow_load("/check-primed.js"));
+    let first_load_time = start.elapsed();
+
+    assert_eq!(asset, b"primed".to_vec());
+    assert!(prime_time >= Duration::from_millis(50));
+    assert!(first_load_time < Duration::from_millis(50));
+  }
+}
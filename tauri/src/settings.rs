@@ -12,6 +12,9 @@ pub struct Settings {
   /// Whether the user allows notifications or not.
   #[cfg(notification)]
   pub allow_notification: Option<bool>,
+  /// The directories the user granted the file system APIs access to.
+  #[serde(default)]
+  pub granted_folders: Vec<String>,
 }
 
 /// Gets the path to the settings file
@@ -0,0 +1,87 @@
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// When the startup began, i.e. when the app started running.
+static START: Lazy<Instant> = Lazy::new(Instant::now);
+
+/// The metrics recorded so far.
+static METRICS: Lazy<Mutex<StartupMetrics>> = Lazy::new(Default::default);
+
+/// The startup phases, in order.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Phase {
+  /// The config was loaded.
+  ConfigLoad,
+  /// The content was set up, e.g. the dev server was reached or the assets were inlined.
+  ContentSetup,
+  /// The webview was built.
+  WebviewBuild,
+  /// The embedded server was bound to its port.
+  ServerBind,
+  /// The main content sent its first `__initialized` message.
+  Initialized,
+}
+
+/// The startup timing metrics, in milliseconds since the startup began.
+/// The phases that didn't run, or didn't end yet, are `None`.
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct StartupMetrics {
+  config_load: Option<f64>,
+  content_setup: Option<f64>,
+  webview_build: Option<f64>,
+  server_bind: Option<f64>,
+  initialized: Option<f64>,
+}
+
+/// Marks the start of the startup, from which the phases are timed.
+pub(crate) fn start() {
+  Lazy::force(&START);
+}
+
+/// Records the end of the given phase, returning whether it wasn't recorded yet.
+/// Only the first end of a phase is kept, e.g. the first `__initialized` message.
+pub(crate) fn record(phase: Phase) -> bool {
+  let elapsed = START.elapsed().as_secs_f64() * 1000.0;
+  let mut metrics = METRICS.lock().expect("Failed to lock startup metrics");
+  let metric = match phase {
+    Phase::ConfigLoad => &mut metrics.config_load,
+    Phase::ContentSetup => &mut metrics.content_setup,
+    Phase::WebviewBuild => &mut metrics.webview_build,
+    Phase::ServerBind => &mut metrics.server_bind,
+    Phase::Initialized => &mut metrics.initialized,
+  };
+  if metric.is_some() {
+    return false;
+  }
+  *metric = Some(elapsed);
+  true
+}
+
+/// Gets the metrics recorded so far.
+pub(crate) fn metrics() -> StartupMetrics {
+  METRICS
+    .lock()
+    .expect("Failed to lock startup metrics")
+    .clone()
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  // check that only the first end of a phase is recorded
+  fn check_record() {
+    start();
+    assert_eq!(metrics().initialized, None);
+    assert!(record(Phase::Initialized));
+    let initialized = metrics().initialized;
+    assert!(initialized.is_some());
+    assert!(!record(Phase::Initialized));
+    assert_eq!(metrics().initialized, initialized);
+    assert_eq!(metrics().server_bind, None);
+  }
+}
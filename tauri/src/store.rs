@@ -0,0 +1,72 @@
+use anyhow::anyhow;
+use once_cell::sync::Lazy;
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri_api::file::read_string;
+use tauri_api::path::{resolve_path, BaseDirectory};
+
+type Store = HashMap<String, JsonValue>;
+
+/// The in-memory store, lazily loaded from the file system on first access.
+static STORE: Lazy<Mutex<Option<Store>>> = Lazy::new(|| Mutex::new(None));
+
+/// Gets the path to the store file
+fn get_store_path() -> tauri_api::Result<PathBuf> {
+  resolve_path(".tauri-store.json", Some(BaseDirectory::App))
+}
+
+/// Reads the store from the file system.
+fn read_store() -> crate::Result<Store> {
+  let store_path = get_store_path()?;
+  if store_path.exists() {
+    read_string(store_path)
+      .and_then(|store| serde_json::from_str(store.as_str()).map_err(|e| anyhow!(e)))
+  } else {
+    Ok(Default::default())
+  }
+}
+
+/// Runs the given closure with the in-memory store, loading it if needed.
+fn with_store<R, F: FnOnce(&mut Store) -> R>(f: F) -> crate::Result<R> {
+  let mut store = STORE.lock().expect("Failed to lock store mutex");
+  if store.is_none() {
+    *store = Some(read_store()?);
+  }
+  Ok(f(store.as_mut().expect("failed to get store")))
+}
+
+/// Gets the value associated with the given key.
+pub fn get(key: impl AsRef<str>) -> crate::Result<Option<JsonValue>> {
+  with_store(|store| store.get(key.as_ref()).cloned())
+}
+
+/// Sets the value of the given key.
+/// The change is kept in memory until `save` is called.
+pub fn set(key: impl Into<String>, value: JsonValue) -> crate::Result<()> {
+  with_store(|store| {
+    store.insert(key.into(), value);
+  })
+}
+
+/// Deletes the given key, returning whether it existed or not.
+/// The change is kept in memory until `save` is called.
+pub fn delete(key: impl AsRef<str>) -> crate::Result<bool> {
+  with_store(|store| store.remove(key.as_ref()).is_some())
+}
+
+/// Writes the store to the file system.
+pub fn save() -> crate::Result<()> {
+  let contents = with_store(|store| serde_json::to_string(store))??;
+  let store_path = get_store_path()?;
+  let store_folder = Path::new(&store_path).parent().unwrap();
+  if !store_folder.exists() {
+    std::fs::create_dir_all(store_folder)?;
+  }
+  File::create(store_path)
+    .map_err(|e| anyhow!(e))
+    .and_then(|mut f| f.write_all(contents.as_bytes()).map_err(|err| anyhow!(err)))
+}
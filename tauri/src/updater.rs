@@ -0,0 +1,106 @@
+use std::io::{BufRead, BufReader};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tauri_api::config::get;
+
+/// Whether the updater is running in apply mode.
+static INSTALLING: AtomicBool = AtomicBool::new(false);
+
+/// Marks an update install in progress until it's dropped, so only one install runs at a time.
+pub(crate) struct InstallGuard(());
+
+impl InstallGuard {
+  /// Starts an install, or returns `None` if one is already in progress.
+  pub(crate) fn acquire() -> Option<Self> {
+    INSTALLING
+      .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+      .ok()
+      .map(|_| Self(()))
+  }
+}
+
+impl Drop for InstallGuard {
+  fn drop(&mut self) {
+    INSTALLING.store(false, Ordering::SeqCst);
+  }
+}
+
+/// Runs the updater in apply mode, emitting the `updater-status` event with its progress,
+/// i.e. the lines it prints, and with the `installed` status once it's done,
+/// so the frontend can ask the user to relaunch the app.
+///
+/// Fails if an update is already being installed, or if the app isn't running from an installed location,
+/// since a disk image, a translocated or a temporary path can't be replaced.
+pub(crate) fn install(handle: &crate::Handle<()>) -> crate::Result<()> {
+  let _guard = InstallGuard::acquire()
+    .ok_or_else(|| crate::Error::Updater("an update is already being installed".into()))?;
+  let location = tauri_api::install::location()?;
+  if !location.installed {
+    return Err(
+      crate::Error::Updater(format!(
+        "the app is not running from an installed location ({}); it must be moved before updating",
+        location.path
+      ))
+      .into(),
+    );
+  }
+  let config = get()?;
+  let _ = crate::event::emit(
+    handle,
+    "updater-status",
+    Some(serde_json::json!({ "status": "installing" })),
+  );
+  // killChildren must not interrupt a self-update
+  let mut child = tauri_api::command::spawn_protected_command(
+    "updater".to_string(),
+    Vec::new(),
+    tauri_api::command::SpawnStdio {
+      stdout: std::process::Stdio::piped(),
+      ..Default::default()
+    },
+    &config.tauri.updater.env,
+  )?;
+  if let Some(stdout) = child.stdout.take() {
+    for line in BufReader::new(stdout).lines().filter_map(|line| line.ok()) {
+      let message = line.trim();
+      if !message.is_empty() {
+        let _ = crate::event::emit(
+          handle,
+          "updater-status",
+          Some(serde_json::json!({ "status": "progress", "message": message })),
+        );
+      }
+    }
+  }
+  let status = child.wait()?;
+  if !status.success() {
+    let error = format!("the updater exited with {}", status);
+    let _ = crate::event::emit(
+      handle,
+      "updater-status",
+      Some(serde_json::json!({ "status": "failed", "error": error })),
+    );
+    return Err(crate::Error::Updater(error).into());
+  }
+  let _ = crate::event::emit(
+    handle,
+    "updater-status",
+    Some(serde_json::json!({ "status": "installed", "relaunchRequired": true })),
+  );
+  Ok(())
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  // check that only one install can run at a time
+  fn check_install_guard() {
+    let guard = InstallGuard::acquire();
+    assert!(guard.is_some());
+    assert!(InstallGuard::acquire().is_none());
+    drop(guard);
+    assert!(InstallGuard::acquire().is_some());
+  }
+}